@@ -1,34 +1,95 @@
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    to_json_binary, Addr, Binary, Deps, DepsMut, Env, Event, MessageInfo, Response, StdResult,
+    to_json_binary, Addr, BankMsg, Binary, BlockInfo, CosmosMsg, Deps, DepsMut, Env, GovMsg,
+    IbcMsg, MessageInfo, Order, Reply, Response, StdResult, Storage, SubMsg, Timestamp, Uint128,
+    WasmMsg,
 };
 
 use crate::error::ContractError;
-use crate::msg::{ExecuteMsg, InstantiateMsg, QueryMsg};
-use crate::state::{CompletedTransactions, PendingTransactions, Transaction, ADMINS, COMPLETED_TXS, PENDING_TXS, QUORUM, SIGNED_TX, TX_COUNTER};
+use crate::events::{
+    addrs_to_string, coins_to_string, config_event, event, list_to_string, owner_event,
+    propose_event, tx_event, ALLOWLIST_EVENT, CONFIG_EVENT, CONTACT_EVENT, HOOK_EVENT,
+    MODULE_EVENT, NONE, PAUSE_EVENT, RECOVERY_EVENT, ROLE_EVENT, SCHEDULE_EVENT, STREAM_EVENT,
+};
+use crate::msg::{
+    ExecuteMsg, GroupExecuteMsg, GroupQueryMsg, GuardCheckResp, GuardExecuteMsg, GuardQueryMsg,
+    InstantiateMsg, MemberResp, ProposalStatus, QueryMsg, TotalWeightResp,
+};
+use crate::proto;
+use crate::state::{
+    CompletedTransactions, PendingTransactions, RateLimit, Role, SelfTx, Transaction, TxMsg,
+    TxStatus, Vote, ADMINS, ALLOWLIST, ALLOWLIST_MODE, AMOUNT_TIERS, AUTO_EXECUTE, BALLOTS,
+    COMPLETED_TXS, GROUP, GUARD, GUARDIANS, HOOKS, MODULES, OUTFLOWS, PAUSE, PAUSE_COOLDOWN,
+    PAUSE_DURATION, PENDING_RATE_LIMITS, PENDING_TXS, PERMISSIONLESS_EXECUTION, PROPOSALS, QUORUM,
+    RATE_LIMITS, RATE_LIMIT_DELAY, SCHEDULE_COUNTER, SIGNED_TX, STREAM_COUNTER, THRESHOLDS,
+    TX_COUNTER,
+};
+
+/// How long a pause lasts when the instantiate message doesn't specify it
+pub const DEFAULT_PAUSE_DURATION: u64 = 3 * 24 * 60 * 60;
+/// How long a loosened rate limit waits when the instantiate message doesn't specify it
+pub const DEFAULT_RATE_LIMIT_DELAY: u64 = 24 * 60 * 60;
+pub const EXECUTE_TX_REPLY_ID: u64 = 1;
+pub const AUTO_EXECUTE_REPLY_ID: u64 = 2;
+pub const ADD_HOOK_REPLY_ID: u64 = 3;
+pub const NOTIFY_HOOK_REPLY_ID: u64 = 4;
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     _info: MessageInfo,
     msg: InstantiateMsg,
 ) -> Result<Response, ContractError> {
-    if msg.owners.len() == 0 {
+    let total_weight = match &msg.group {
+        Some(group) => {
+            if !msg.owners.is_empty() {
+                return Err(ContractError::GroupManaged(group.to_string()));
+            }
+            let resp: TotalWeightResp = deps
+                .querier
+                .query_wasm_smart(group, &GroupQueryMsg::TotalWeight { at_height: None })?;
+            weight_to_u32(resp.weight)?
+        }
+        None => msg.owners.len() as u32,
+    };
+
+    if total_weight == 0 {
         return Err(ContractError::ZeroOwners);
     }
 
-    if msg.quorum > msg.owners.len() as u32 {
+    if msg.quorum > total_weight {
         return Err(ContractError::WrongQuorum {
             quorum: msg.quorum,
-            owners: msg.owners.len() as u32,
+            owners: total_weight,
         });
     }
 
     ADMINS.save(deps.storage, &msg.owners)?;
     QUORUM.save(deps.storage, &msg.quorum)?;
     TX_COUNTER.save(deps.storage, &0u128)?;
+    SCHEDULE_COUNTER.save(deps.storage, &0u64)?;
+    STREAM_COUNTER.save(deps.storage, &0u64)?;
+    PAUSE_DURATION.save(
+        deps.storage,
+        &msg.pause_duration.unwrap_or(DEFAULT_PAUSE_DURATION),
+    )?;
+    PERMISSIONLESS_EXECUTION.save(deps.storage, &msg.permissionless_execution.unwrap_or(false))?;
+    AUTO_EXECUTE.save(deps.storage, &msg.auto_execute.unwrap_or(false))?;
+    RATE_LIMIT_DELAY.save(
+        deps.storage,
+        &msg.rate_limit_delay.unwrap_or(DEFAULT_RATE_LIMIT_DELAY),
+    )?;
+    ALLOWLIST_MODE.save(deps.storage, &msg.allowlist.is_some())?;
+    for addr in msg.allowlist.iter().flatten() {
+        ALLOWLIST.save(deps.storage, addr, &true)?;
+    }
+
+    if let Some(guardians) = msg.guardians {
+        exec::validate_guardians(&guardians)?;
+        GUARDIANS.save(deps.storage, &guardians)?;
+    }
 
     let pending_txs = PendingTransactions::new(Vec::new());
     PENDING_TXS.save(deps.storage, &pending_txs)?;
@@ -38,154 +99,2068 @@ pub fn instantiate(
 
     SIGNED_TX.save(deps.storage, (Addr::unchecked("test"), 0), &false)?;
 
-    let events = msg
-        .owners
-        .into_iter()
-        .map(|owner| Event::new("owner-added").add_attribute("addr", owner));
+    let events = msg.owners.iter().map(|owner| owner_event("add", owner));
+
+    let response = Response::new()
+        .add_event(
+            event(CONFIG_EVENT, "instantiate")
+                .add_attribute("owners", addrs_to_string(&msg.owners))
+                .add_attribute("quorum", msg.quorum.to_string())
+                .add_attribute(
+                    "group",
+                    msg.group.as_ref().map_or(NONE, |group| group.as_str()),
+                ),
+        )
+        .add_events(events);
 
-    Ok(Response::new().add_events(events))
+    // Only the group's admin can add hooks, failing to add it doesn't fail the instantiation
+    Ok(match msg.group {
+        Some(group) => {
+            GROUP.save(deps.storage, &group)?;
+            let msg = WasmMsg::Execute {
+                contract_addr: group.to_string(),
+                msg: to_json_binary(&GroupExecuteMsg::AddHook {
+                    addr: env.contract.address.to_string(),
+                })?,
+                funds: vec![],
+            };
+            response.add_submessage(SubMsg::reply_on_error(msg, ADD_HOOK_REPLY_ID))
+        }
+        None => response,
+    })
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn execute(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
     msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
-    exec::is_admin(&deps, &info)?;
     match msg {
-        ExecuteMsg::CreateTransaction { tx_msg } => exec::create_tx(deps, info, tx_msg),
-        ExecuteMsg::SignTransactions { tx_id } => exec::sign_tx(deps, info, tx_id),
-        ExecuteMsg::ExecuteTransaction { tx_id } => exec::execute_tx(deps, tx_id),
+        ExecuteMsg::CreateTransaction {
+            tx_msg,
+            auto_execute,
+        } => {
+            exec::assert_role(&deps, &info, Role::Proposer)?;
+            exec::create_tx(deps, env, info, tx_msg, auto_execute, None)
+        }
+        ExecuteMsg::SignTransactions { tx_id } => {
+            exec::assert_role(&deps, &info, Role::Signer)?;
+            exec::sign_tx(deps, env, info, tx_id)
+        }
+        ExecuteMsg::ExecuteTransaction { tx_id } => {
+            // Quorum is still checked by execute_tx, so relayers can execute approved transactions.
+            // The wallet calls itself when auto-executing.
+            if !PERMISSIONLESS_EXECUTION.load(deps.storage)? && info.sender != env.contract.address
+            {
+                exec::assert_role(&deps, &info, Role::Executor)?;
+            }
+            exec::execute_tx(deps, env, info, tx_id)
+        }
+        ExecuteMsg::ProposeRecovery { owners, quorum } => {
+            exec::is_guardian(&deps, &info)?;
+            exec::propose_recovery(deps, env, info, owners, quorum)
+        }
+        ExecuteMsg::ApproveRecovery {} => {
+            exec::is_guardian(&deps, &info)?;
+            exec::approve_recovery(deps, info)
+        }
+        ExecuteMsg::ExecuteRecovery {} => {
+            exec::is_guardian(&deps, &info)?;
+            exec::execute_recovery(deps, env, info)
+        }
+        ExecuteMsg::VetoRecovery {} => {
+            exec::assert_role(&deps, &info, Role::Signer)?;
+            exec::veto_recovery(deps, info)
+        }
+        ExecuteMsg::Pause {} => {
+            exec::assert_role(&deps, &info, Role::Signer)?;
+            exec::pause(deps, env, info)
+        }
+        ExecuteMsg::RunDueSchedules {} => exec::run_due_schedules(deps, env),
+        ExecuteMsg::ClaimStream { id, amount } => exec::claim_stream(deps, env, info, id, amount),
+        ExecuteMsg::ExecuteFromModule { msgs } => exec::execute_from_module(deps, env, info, msgs),
+        ExecuteMsg::Propose {
+            title,
+            description,
+            msgs,
+            latest,
+        } => {
+            exec::assert_role(&deps, &info, Role::Proposer)?;
+            exec::propose(deps, env, info, title, description, msgs, latest)
+        }
+        ExecuteMsg::Vote { proposal_id, vote } => {
+            exec::assert_role(&deps, &info, Role::Signer)?;
+            exec::vote(deps, env, info, proposal_id.into(), vote)
+        }
+        ExecuteMsg::Execute { proposal_id } => execute(
+            deps,
+            env,
+            info,
+            ExecuteMsg::ExecuteTransaction {
+                tx_id: proposal_id.into(),
+            },
+        ),
+        ExecuteMsg::Close { proposal_id } => exec::close(deps, env, info, proposal_id.into()),
+        ExecuteMsg::MemberChangedHook { diffs } => exec::member_changed_hook(deps, info, diffs),
+        ExecuteMsg::SendMessages { msgs } => exec::send_messages(env, info, msgs),
+    }
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn reply(deps: DepsMut, env: Env, msg: Reply) -> Result<Response, ContractError> {
+    match msg.id {
+        EXECUTE_TX_REPLY_ID => exec::handle_execute_reply(deps, env, msg.result),
+        AUTO_EXECUTE_REPLY_ID => exec::handle_auto_execute_reply(deps, env, msg.result),
+        ADD_HOOK_REPLY_ID => exec::handle_add_hook_reply(deps, msg.result),
+        NOTIFY_HOOK_REPLY_ID => Ok(exec::handle_notify_hook_reply(msg.result)),
+        _ => Err(ContractError::UnknownReplyID {}),
+    }
+}
+
+/// Signatures `tx_msg` needs, the threshold of its category or else the quorum.
+/// Transfers need at least the signatures of the amount tier of every sent coin,
+/// schedules and streams that of their total payments.
+/// Lifting a pause always needs the quorum, so a lowered threshold can't undo it.
+fn required_signatures(storage: &dyn Storage, tx_msg: &TxMsg) -> StdResult<u32> {
+    let mut required = match THRESHOLDS.may_load(storage, tx_msg.category().as_str())? {
+        Some(threshold) => threshold,
+        None => QUORUM.load(storage)?,
+    };
+    if *tx_msg == TxMsg::TxSelf(SelfTx::Unpause {}) {
+        required = required.max(QUORUM.load(storage)?);
+    }
+
+    for coin in tx_msg.committed() {
+        let tiers = AMOUNT_TIERS
+            .may_load(storage, &coin.denom)?
+            .unwrap_or_default();
+        let tier = tiers
+            .iter()
+            .find(|tier| tier.up_to.is_none_or(|up_to| coin.amount <= up_to));
+        if let Some(tier) = tier {
+            required = required.max(tier.threshold);
+        }
+    }
+
+    Ok(required)
+}
+
+/// Messages the wallet sends when executing `tx_msg`, changes to the wallet itself are
+/// applied directly and send none
+fn cosmos_msgs(env: &Env, tx_msg: &TxMsg) -> Vec<CosmosMsg> {
+    let granter = &env.contract.address;
+    let message: CosmosMsg = match tx_msg.clone() {
+        TxMsg::TxSelf(_) => return vec![],
+        TxMsg::Messages { msgs } => return msgs,
+        TxMsg::TxBank { to, coins } => BankMsg::Send {
+            to_address: to.to_string(),
+            amount: coins,
+        }
+        .into(),
+        TxMsg::IbcTransfer {
+            channel_id,
+            to_address,
+            amount,
+            timeout,
+        } => IbcMsg::Transfer {
+            channel_id,
+            to_address,
+            amount,
+            timeout: timeout.to_ibc_timeout(env),
+        }
+        .into(),
+        TxMsg::GovVote { proposal_id, vote } => GovMsg::Vote { proposal_id, vote }.into(),
+        TxMsg::GovVoteWeighted {
+            proposal_id,
+            options,
+        } => GovMsg::VoteWeighted {
+            proposal_id,
+            options,
+        }
+        .into(),
+        TxMsg::AuthzGrant {
+            grantee,
+            authorization,
+            expiration,
+        } => proto::grant(granter, &grantee, &authorization, expiration),
+        TxMsg::AuthzRevoke {
+            grantee,
+            msg_type_url,
+        } => proto::revoke(granter, &grantee, &msg_type_url),
+        TxMsg::GrantAllowance {
+            grantee,
+            spend_limit,
+            expiration,
+        } => proto::grant_allowance(granter, &grantee, &spend_limit, expiration),
+        TxMsg::RevokeAllowance { grantee } => proto::revoke_allowance(granter, &grantee),
+    };
+    vec![message]
+}
+
+/// cw3 status of `tx`. A pending transaction is rejected once it expired without reaching
+/// its signatures, or once the owners that voted against it leave too few to reach them.
+fn proposal_status(deps: Deps, block: &BlockInfo, tx: &Transaction) -> StdResult<ProposalStatus> {
+    let storage = deps.storage;
+    match tx.status {
+        TxStatus::Executed => return Ok(ProposalStatus::Executed),
+        TxStatus::Rejected => return Ok(ProposalStatus::Rejected),
+        TxStatus::Pending | TxStatus::ExecutionFailed { .. } => {}
+    }
+
+    let required = required_signatures(storage, &tx.tx_msg)? as u64;
+    if tx.num_confirmations as u64 >= required {
+        return Ok(ProposalStatus::Passed);
+    }
+
+    let expired = PROPOSALS.load(storage, tx.id)?.expires.is_expired(block);
+    let mut against = 0;
+    for ballot in BALLOTS
+        .prefix(tx.id)
+        .range(storage, None, None, Order::Ascending)
+    {
+        let (_, ballot) = ballot?;
+        if ballot.vote != Vote::Yes {
+            against += ballot.weight;
+        }
+    }
+    let total = total_weight(deps)?;
+
+    if expired || total.saturating_sub(against) < required {
+        Ok(ProposalStatus::Rejected)
+    } else {
+        Ok(ProposalStatus::Open)
+    }
+}
+
+/// Voting weight of `addr`, read from the cw4 group if the wallet has one and 1 for the
+/// owners otherwise. Group members without weight can't vote.
+fn member_weight(deps: Deps, addr: &Addr) -> StdResult<Option<u64>> {
+    match GROUP.may_load(deps.storage)? {
+        Some(group) => {
+            let resp: MemberResp = deps.querier.query_wasm_smart(
+                group,
+                &GroupQueryMsg::Member {
+                    addr: addr.to_string(),
+                    at_height: None,
+                },
+            )?;
+            Ok(resp.weight.filter(|weight| *weight > 0))
+        }
+        None => Ok(ADMINS.load(deps.storage)?.contains(addr).then_some(1)),
+    }
+}
+
+/// Summed weight of the owners, no threshold can require more
+fn total_weight(deps: Deps) -> StdResult<u64> {
+    match GROUP.may_load(deps.storage)? {
+        Some(group) => {
+            let resp: TotalWeightResp = deps
+                .querier
+                .query_wasm_smart(group, &GroupQueryMsg::TotalWeight { at_height: None })?;
+            Ok(resp.weight)
+        }
+        None => Ok(ADMINS.load(deps.storage)?.len() as u64),
+    }
+}
+
+/// cw4 weights are u64 while signatures are counted in u32
+fn weight_to_u32(weight: u64) -> Result<u32, ContractError> {
+    u32::try_from(weight).map_err(|_| ContractError::WeightTooLarge(weight))
+}
+
+/// Rate limit of `denom` at `now`, a loosened limit applies once its timelock passed
+fn rate_limit(storage: &dyn Storage, now: Timestamp, denom: &str) -> StdResult<Option<RateLimit>> {
+    match PENDING_RATE_LIMITS.may_load(storage, denom)? {
+        Some(pending) if pending.effective_at <= now => Ok(pending.limit),
+        _ => RATE_LIMITS.may_load(storage, denom),
+    }
+}
+
+/// Whether a rate limit is in force for any denom at `now`, a timelocked removal counts
+/// once it took effect
+fn rate_limited(storage: &dyn Storage, now: Timestamp) -> StdResult<bool> {
+    let denoms = RATE_LIMITS
+        .keys(storage, None, None, Order::Ascending)
+        .chain(PENDING_RATE_LIMITS.keys(storage, None, None, Order::Ascending))
+        .collect::<StdResult<Vec<_>>>()?;
+    for denom in denoms {
+        if rate_limit(storage, now, &denom)?.is_some() {
+            return Ok(true);
+        }
     }
+
+    Ok(false)
+}
+
+/// Amount of `denom` that left the wallet within the `window` seconds before `now`
+fn outflow(storage: &dyn Storage, now: Timestamp, denom: &str, window: u64) -> StdResult<Uint128> {
+    let since = now.minus_seconds(window);
+    Ok(OUTFLOWS
+        .may_load(storage, denom)?
+        .unwrap_or_default()
+        .iter()
+        .filter(|outflow| outflow.at > since)
+        .map(|outflow| outflow.amount)
+        .sum())
 }
 
-mod exec {
-    use super::*;
-    use crate::state::{TxMsg, PENDING_TXS, SIGNED_TX};
-    use cosmwasm_std::{Addr, BankMsg};
+mod exec {
+    use super::*;
+    use crate::msg::{HookExecuteMsg, MemberDiff};
+    use crate::state::{
+        cw20_transfer, msg_funds, msg_unverifiable, msgs_outflow, AmountTier, Authorization,
+        Ballot, Contact, GuardianSet, Outflow, PauseInfo, PendingRateLimit, ProposalInfo, Recovery,
+        Schedule, SelfTx, Stream, TxCategory, CONTACTS, PENDING_TXS, RECOVERY, ROLES, SCHEDULES,
+        SIGNED_TX, STREAMS, STREAM_RESERVED, TEMP_AUTO_EXECUTE_TX, TEMP_EXECUTING_TX,
+    };
+    use cosmwasm_std::{Addr, Coin, Event, SubMsgResult, Timestamp, Uint128};
+    use cw_utils::Expiration;
+    use std::collections::BTreeMap;
+
+    pub fn make_next_id(deps: DepsMut) -> StdResult<u128> {
+        let mut tx_counter = TX_COUNTER.load(deps.storage)?;
+        tx_counter += 1;
+        TX_COUNTER.save(deps.storage, &tx_counter)?;
+        Ok(tx_counter)
+    }
+
+    pub fn create_tx(
+        mut deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        tx_msg: TxMsg,
+        auto_execute: Option<bool>,
+        proposal: Option<ProposalInfo>,
+    ) -> Result<Response, ContractError> {
+        // Proposing to unpause has to stay possible while the wallet is paused
+        if tx_msg != TxMsg::TxSelf(SelfTx::Unpause {}) {
+            assert_not_paused(&deps, &env)?;
+        }
+
+        let weight = member_weight(deps.as_ref(), &info.sender)?;
+
+        let mut pending_txs = PENDING_TXS.load(deps.storage)?;
+        let next_id = make_next_id(deps.branch())?;
+        let mut tx = Transaction::new(tx_msg, next_id);
+
+        tx.auto_execute = auto_execute;
+
+        let proposal = proposal.unwrap_or_else(|| ProposalInfo {
+            title: tx.tx_msg.msg_type().to_string(),
+            description: tx.tx_msg.to_string(),
+            proposer: info.sender.clone(),
+            expires: Expiration::Never {},
+        });
+        PROPOSALS.save(deps.storage, next_id, &proposal)?;
+
+        // Since the user proposed the tx he already approves that it will be executed,
+        // This way he won't have to approve the transaction again.
+        // Proposers that aren't owners can't sign, so their proposal starts unsigned.
+        if let Some(weight) = weight {
+            tx.num_confirmations = weight_to_u32(weight)?;
+            SIGNED_TX.save(deps.storage, (info.sender.clone(), next_id), &true)?;
+            BALLOTS.save(
+                deps.storage,
+                (next_id, &info.sender),
+                &Ballot {
+                    vote: Vote::Yes,
+                    weight,
+                },
+            )?;
+        }
+
+        pending_txs.push(tx.clone());
+        PENDING_TXS.save(deps.storage, &pending_txs)?;
+
+        let contact = match tx.tx_msg.recipient() {
+            Some(recipient) => CONTACTS.may_load(deps.storage, recipient)?,
+            None => None,
+        };
+        let response =
+            Response::new().add_event(propose_event(&tx, &info.sender, contact.as_ref()));
+
+        Ok(match auto_execute_msg(deps, &env, &tx)? {
+            Some(msg) => response.add_submessage(msg),
+            None => response,
+        })
+    }
+
+    /// Self-call executing `tx` once it reached quorum, if auto-execute is enabled for it.
+    /// Running it as a submessage reverts a failed execution without reverting the signature.
+    fn auto_execute_msg(
+        deps: DepsMut,
+        env: &Env,
+        tx: &Transaction,
+    ) -> Result<Option<SubMsg>, ContractError> {
+        let auto_execute = match tx.auto_execute {
+            Some(auto_execute) => auto_execute,
+            None => AUTO_EXECUTE.load(deps.storage)?,
+        };
+
+        if !auto_execute || tx.num_confirmations < required_signatures(deps.storage, &tx.tx_msg)? {
+            return Ok(None);
+        }
+
+        TEMP_AUTO_EXECUTE_TX.save(deps.storage, &tx.id)?;
+
+        let msg = WasmMsg::Execute {
+            contract_addr: env.contract.address.to_string(),
+            msg: to_json_binary(&ExecuteMsg::ExecuteTransaction { tx_id: tx.id })?,
+            funds: vec![],
+        };
+        Ok(Some(SubMsg::reply_always(msg, AUTO_EXECUTE_REPLY_ID)))
+    }
+
+    pub fn handle_auto_execute_reply(
+        deps: DepsMut,
+        env: Env,
+        result: SubMsgResult,
+    ) -> Result<Response, ContractError> {
+        let tx_id = TEMP_AUTO_EXECUTE_TX.load(deps.storage)?;
+        TEMP_AUTO_EXECUTE_TX.remove(deps.storage);
+
+        match result {
+            SubMsgResult::Ok(_) => Ok(Response::new()),
+            // The signature that triggered the execution is kept, the transaction stays pending
+            SubMsgResult::Err(error) => {
+                let pending_txs = PENDING_TXS.load(deps.storage)?;
+                let tx = pending_txs
+                    .find(tx_id)
+                    .ok_or(ContractError::NonExistentTx(tx_id))?;
+
+                Ok(Response::new().add_event(
+                    tx_event("auto_execute_failed", tx, &env.contract.address)
+                        .add_attribute("error", error),
+                ))
+            }
+        }
+    }
+
+    pub fn sign_tx(
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        tx_id: u128,
+    ) -> Result<Response, ContractError> {
+        if let Ok(signed) = SIGNED_TX.load(deps.storage, (info.sender.clone(), tx_id)) {
+            if signed {
+                return Err(ContractError::AlreadySigned(tx_id));
+            }
+        }
+        if BALLOTS.has(deps.storage, (tx_id, &info.sender)) {
+            return Err(ContractError::AlreadyVoted(tx_id));
+        }
+        assert_not_expired(&deps, &env, tx_id)?;
+        let weight =
+            member_weight(deps.as_ref(), &info.sender)?.ok_or(ContractError::Unauthorized {})?;
+
+        SIGNED_TX.save(deps.storage, (info.sender.clone(), tx_id), &true)?;
+        BALLOTS.save(
+            deps.storage,
+            (tx_id, &info.sender),
+            &Ballot {
+                vote: Vote::Yes,
+                weight,
+            },
+        )?;
+
+        let mut pending_txs = PENDING_TXS.load(deps.storage)?;
+
+        let tx = pending_txs
+            .find_mut(tx_id)
+            .ok_or(ContractError::NonExistentTx(tx_id))?;
+
+        tx.num_confirmations = tx
+            .num_confirmations
+            .checked_add(weight_to_u32(weight)?)
+            .ok_or(ContractError::WeightTooLarge(weight))?;
+        let tx = tx.clone();
+
+        PENDING_TXS.save(deps.storage, &pending_txs)?;
+
+        let response = Response::new().add_event(tx_event("sign", &tx, &info.sender));
+
+        Ok(match auto_execute_msg(deps, &env, &tx)? {
+            Some(msg) => response.add_submessage(msg),
+            None => response,
+        })
+    }
+
+    fn assert_not_expired(deps: &DepsMut, env: &Env, tx_id: u128) -> Result<(), ContractError> {
+        let expired = PROPOSALS
+            .may_load(deps.storage, tx_id)?
+            .is_some_and(|proposal| proposal.expires.is_expired(&env.block));
+        if expired {
+            return Err(ContractError::Expired(tx_id));
+        }
+        Ok(())
+    }
+
+    /// cw3 proposal of arbitrary messages, the proposer's vote is counted like with
+    /// `create_tx`
+    pub fn propose(
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        title: String,
+        description: String,
+        msgs: Vec<CosmosMsg>,
+        latest: Option<Expiration>,
+    ) -> Result<Response, ContractError> {
+        let expires = latest.unwrap_or_default();
+        if expires.is_expired(&env.block) {
+            return Err(ContractError::WrongExpiration);
+        }
+
+        let proposal = ProposalInfo {
+            title,
+            description,
+            proposer: info.sender.clone(),
+            expires,
+        };
+        create_tx(
+            deps,
+            env,
+            info,
+            TxMsg::Messages { msgs },
+            None,
+            Some(proposal),
+        )
+    }
+
+    /// cw3 vote, a yes vote signs the transaction. Other votes don't change the signatures
+    /// but can get the transaction rejected.
+    pub fn vote(
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        tx_id: u128,
+        vote: Vote,
+    ) -> Result<Response, ContractError> {
+        if vote == Vote::Yes {
+            return sign_tx(deps, env, info, tx_id);
+        }
+
+        if SIGNED_TX.has(deps.storage, (info.sender.clone(), tx_id))
+            || BALLOTS.has(deps.storage, (tx_id, &info.sender))
+        {
+            return Err(ContractError::AlreadyVoted(tx_id));
+        }
+        let pending_txs = PENDING_TXS.load(deps.storage)?;
+        let tx = pending_txs
+            .find(tx_id)
+            .ok_or(ContractError::NonExistentTx(tx_id))?;
+        assert_not_expired(&deps, &env, tx_id)?;
+        let weight =
+            member_weight(deps.as_ref(), &info.sender)?.ok_or(ContractError::Unauthorized {})?;
+
+        BALLOTS.save(
+            deps.storage,
+            (tx_id, &info.sender),
+            &Ballot {
+                vote: vote.clone(),
+                weight,
+            },
+        )?;
+
+        Ok(Response::new().add_event(
+            tx_event("vote", tx, &info.sender).add_attribute("ballot", vote.to_string()),
+        ))
+    }
+
+    /// Moves a rejected transaction to the completed ones
+    pub fn close(
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        tx_id: u128,
+    ) -> Result<Response, ContractError> {
+        let mut pending_txs = PENDING_TXS.load(deps.storage)?;
+        let tx = pending_txs
+            .find(tx_id)
+            .ok_or(ContractError::NonExistentTx(tx_id))?
+            .clone();
+
+        if proposal_status(deps.as_ref(), &env.block, &tx)? != ProposalStatus::Rejected {
+            return Err(ContractError::NotClosable(tx_id));
+        }
+
+        let mut tx = pending_txs
+            .remove(&tx)
+            .ok_or(ContractError::NonExistentTx(tx_id))?;
+        PENDING_TXS.save(deps.storage, &pending_txs)?;
+
+        tx.status = TxStatus::Rejected;
+        let event = tx_event("close", &tx, &info.sender);
+        COMPLETED_TXS.update(deps.storage, |mut txs| -> StdResult<_> {
+            txs.push(tx);
+            Ok(txs)
+        })?;
+
+        Ok(Response::new().add_event(event))
+    }
+
+    /// Drops the votes of members the cw4 group removed from the pending transactions.
+    /// Votes of members whose weight changed keep the weight they were cast with.
+    pub fn member_changed_hook(
+        deps: DepsMut,
+        info: MessageInfo,
+        diffs: Vec<MemberDiff>,
+    ) -> Result<Response, ContractError> {
+        if GROUP.may_load(deps.storage)?.as_ref() != Some(&info.sender) {
+            return Err(ContractError::Unauthorized {});
+        }
+
+        let mut pending_txs = PENDING_TXS.load(deps.storage)?;
+        let mut events = vec![];
+        for diff in diffs {
+            let member = Addr::unchecked(diff.key);
+            let was_member = diff.old.is_some_and(|weight| weight > 0);
+            let is_member = diff.new.is_some_and(|weight| weight > 0);
+
+            if !was_member && is_member {
+                events.push(owner_event("add", &member));
+            }
+            if !was_member || is_member {
+                continue;
+            }
+
+            let mut invalidated = vec![];
+            for tx in pending_txs.0.iter_mut() {
+                let Some(ballot) = BALLOTS.may_load(deps.storage, (tx.id, &member))? else {
+                    continue;
+                };
+                BALLOTS.remove(deps.storage, (tx.id, &member));
+                if ballot.vote == Vote::Yes {
+                    SIGNED_TX.remove(deps.storage, (member.clone(), tx.id));
+                    tx.num_confirmations = tx
+                        .num_confirmations
+                        .saturating_sub(weight_to_u32(ballot.weight)?);
+                }
+                invalidated.push(tx.id);
+            }
+            events.push(
+                owner_event("remove", &member)
+                    .add_attribute("invalidated", list_to_string(invalidated)),
+            );
+        }
+        PENDING_TXS.save(deps.storage, &pending_txs)?;
+
+        Ok(Response::new().add_events(events))
+    }
+
+    pub fn handle_add_hook_reply(
+        deps: DepsMut,
+        result: SubMsgResult,
+    ) -> Result<Response, ContractError> {
+        match result {
+            SubMsgResult::Ok(_) => Ok(Response::new()),
+            SubMsgResult::Err(error) => Ok(Response::new().add_event(
+                event(CONFIG_EVENT, "add_hook_failed")
+                    .add_attribute("group", GROUP.load(deps.storage)?)
+                    .add_attribute("error", error),
+            )),
+        }
+    }
+
+    /// A hook failing to handle a notification doesn't revert the owner change
+    pub fn handle_notify_hook_reply(result: SubMsgResult) -> Response {
+        match result {
+            SubMsgResult::Ok(_) => Response::new(),
+            SubMsgResult::Err(error) => Response::new()
+                .add_event(event(HOOK_EVENT, "notify_failed").add_attribute("error", error)),
+        }
+    }
+
+    pub fn execute_tx(
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        tx_id: u128,
+    ) -> Result<Response, ContractError> {
+        let pending_txs = PENDING_TXS.load(deps.storage)?;
+
+        let tx = pending_txs
+            .find(tx_id)
+            .ok_or(ContractError::NonExistentTx(tx_id))?;
+
+        if tx.tx_msg != TxMsg::TxSelf(SelfTx::Unpause {}) {
+            assert_not_paused(&deps, &env)?;
+        }
+
+        let quorum = required_signatures(deps.storage, &tx.tx_msg)?;
+
+        if quorum > tx.num_confirmations {
+            return Err(ContractError::NotEnoughSignatures {
+                quorum,
+                num_signed: tx.num_confirmations,
+            });
+        }
+
+        if let Some(recipient) = tx.tx_msg.recipient() {
+            assert_allowed_recipient(&deps, recipient)?;
+        }
+        // Addresses of other chains can't be validated, they are allowlisted as they are
+        if let TxMsg::IbcTransfer { to_address, .. } = &tx.tx_msg {
+            assert_allowed_recipient(&deps, &Addr::unchecked(to_address))?;
+        }
+        if let TxMsg::Messages { msgs } = &tx.tx_msg {
+            assert_msgs_recipients(&deps, &env, msgs)?;
+        }
+        // Grantees that can spend the wallet's funds are held to the allowlist as well
+        if let TxMsg::AuthzGrant {
+            grantee,
+            authorization: Authorization::Send { spend_limit },
+            ..
+        }
+        | TxMsg::GrantAllowance {
+            grantee,
+            spend_limit,
+            ..
+        } = &tx.tx_msg
+        {
+            assert_allowed_recipient(&deps, grantee)?;
+            assert_limited_grant(&deps, &env, spend_limit)?;
+        }
+        // A generic grant has no spend limit, nothing would hold the grantee to the rules
+        if let TxMsg::AuthzGrant {
+            authorization: authorization @ Authorization::Generic { msg_type_url },
+            ..
+        } = &tx.tx_msg
+        {
+            if authorization.moves_funds()
+                && (ALLOWLIST_MODE.load(deps.storage)?
+                    || rate_limited(deps.storage, env.block.time)?)
+            {
+                return Err(ContractError::UnrestrictedGrant(msg_type_url.clone()));
+            }
+        }
+
+        check_guard(&deps, tx, &info.sender)?;
+
+        PENDING_TXS.update(deps.storage, |mut txs| -> StdResult<PendingTransactions> {
+            txs.remove(&tx.clone());
+            Ok(txs)
+        })?;
+
+        let mut tx = tx.clone();
+        tx.executor = Some(info.sender.clone());
+
+        match tx.tx_msg.clone() {
+            TxMsg::Messages { msgs } => execute_messages(deps, &env, &info, tx, msgs),
+            TxMsg::TxSelf(self_msg) => {
+                tx.status = TxStatus::Executed;
+                let event = tx_event("execute", &tx, &info.sender);
+                let notify = guard_notify(&deps, &tx, true)?;
+                COMPLETED_TXS.update(deps.storage, |mut txs| -> StdResult<_> {
+                    txs.push(tx);
+                    Ok(txs)
+                })?;
+
+                Ok(execute_self_tx(deps, env, tx_id, self_msg)?
+                    .add_event(event)
+                    .add_messages(notify))
+            }
+            // Transfers, votes and grants are a single message
+            tx_msg => {
+                let message = cosmos_msgs(&env, &tx_msg).remove(0);
+                execute_message(deps, &env, &info, tx, message)
+            }
+        }
+    }
+
+    /// The messages of a cw3 proposal are sent by the wallet to itself, so they succeed or
+    /// fail together and a failure is recorded like for a single message
+    fn execute_messages(
+        deps: DepsMut,
+        env: &Env,
+        info: &MessageInfo,
+        tx: Transaction,
+        msgs: Vec<CosmosMsg>,
+    ) -> Result<Response, ContractError> {
+        let message = WasmMsg::Execute {
+            contract_addr: env.contract.address.to_string(),
+            msg: to_json_binary(&ExecuteMsg::SendMessages { msgs })?,
+            funds: vec![],
+        };
+        execute_message(deps, env, info, tx, message.into())
+    }
+
+    pub fn send_messages(
+        env: Env,
+        info: MessageInfo,
+        msgs: Vec<CosmosMsg>,
+    ) -> Result<Response, ContractError> {
+        if info.sender != env.contract.address {
+            return Err(ContractError::Unauthorized {});
+        }
+
+        Ok(Response::new().add_messages(msgs))
+    }
+
+    /// The outcome is recorded in `reply`, a failed message puts the transaction back to
+    /// pending instead of reverting the whole execution
+    fn execute_message(
+        deps: DepsMut,
+        env: &Env,
+        info: &MessageInfo,
+        tx: Transaction,
+        message: CosmosMsg,
+    ) -> Result<Response, ContractError> {
+        let coins = tx.tx_msg.outflow();
+        assert_unreserved(&deps, env, &coins)?;
+        assert_within_rate_limits(&deps, env, &coins)?;
+
+        let event = tx_event("execute", &tx, &info.sender);
+        TEMP_EXECUTING_TX.save(deps.storage, &tx.id)?;
+        COMPLETED_TXS.update(deps.storage, |mut txs| -> StdResult<_> {
+            txs.push(tx);
+            Ok(txs)
+        })?;
+
+        Ok(Response::new()
+            .add_event(event)
+            .add_submessage(SubMsg::reply_always(message, EXECUTE_TX_REPLY_ID)))
+    }
+
+    pub fn handle_execute_reply(
+        mut deps: DepsMut,
+        env: Env,
+        result: SubMsgResult,
+    ) -> Result<Response, ContractError> {
+        let tx_id = TEMP_EXECUTING_TX.load(deps.storage)?;
+        TEMP_EXECUTING_TX.remove(deps.storage);
+
+        let mut completed_txs = COMPLETED_TXS.load(deps.storage)?;
+
+        match result {
+            SubMsgResult::Ok(_) => {
+                let tx = completed_txs
+                    .find_mut(tx_id)
+                    .ok_or(ContractError::NonExistentTx(tx_id))?;
+                tx.status = TxStatus::Executed;
+                let executor = tx.executor.clone().unwrap_or(env.contract.address.clone());
+                let event = tx_event("executed", tx, &executor);
+                record_outflow(&mut deps, &env, &tx.tx_msg.outflow())?;
+                let notify = guard_notify(&deps, tx, true)?;
+                COMPLETED_TXS.save(deps.storage, &completed_txs)?;
+
+                Ok(Response::new().add_event(event).add_messages(notify))
+            }
+            SubMsgResult::Err(error) => {
+                let mut tx = completed_txs
+                    .remove(tx_id)
+                    .ok_or(ContractError::NonExistentTx(tx_id))?;
+                COMPLETED_TXS.save(deps.storage, &completed_txs)?;
+
+                tx.status = TxStatus::ExecutionFailed {
+                    error: error.clone(),
+                };
+                let executor = tx.executor.clone().unwrap_or(env.contract.address);
+                let event =
+                    tx_event("execution_failed", &tx, &executor).add_attribute("error", error);
+                let notify = guard_notify(&deps, &tx, false)?;
+                PENDING_TXS.update(deps.storage, |mut txs| -> StdResult<_> {
+                    txs.push(tx);
+                    Ok(txs)
+                })?;
+
+                Ok(Response::new().add_event(event).add_messages(notify))
+            }
+        }
+    }
+
+    fn execute_self_tx(
+        deps: DepsMut,
+        env: Env,
+        tx_id: u128,
+        self_msg: SelfTx,
+    ) -> Result<Response, ContractError> {
+        match self_msg {
+            SelfTx::AddOwner { owner, quorum } => add_owner(deps, tx_id, owner, quorum),
+            SelfTx::RemoveOwner { owner, quorum } => remove_owner(deps, tx_id, owner, quorum),
+            SelfTx::UpdateQuorum { quorum } => update_quorum(deps, tx_id, quorum),
+            SelfTx::UpdateGuardians { guardians } => update_guardians(deps, tx_id, guardians),
+            SelfTx::Unpause {} => unpause(deps, env, tx_id),
+            SelfTx::SetPermissionlessExecution { enabled } => {
+                PERMISSIONLESS_EXECUTION.save(deps.storage, &enabled)?;
+                Ok(Response::new().add_event(
+                    config_event("set_permissionless_execution", tx_id)
+                        .add_attribute("enabled", enabled.to_string()),
+                ))
+            }
+            SelfTx::SetAutoExecute { enabled } => {
+                AUTO_EXECUTE.save(deps.storage, &enabled)?;
+                Ok(Response::new().add_event(
+                    config_event("set_auto_execute", tx_id)
+                        .add_attribute("enabled", enabled.to_string()),
+                ))
+            }
+            SelfTx::SetGuard { guard } => {
+                let event = match guard {
+                    Some(guard) => {
+                        GUARD.save(deps.storage, &guard)?;
+                        config_event("set_guard", tx_id).add_attribute("guard", guard)
+                    }
+                    None => {
+                        GUARD.remove(deps.storage);
+                        config_event("set_guard", tx_id).add_attribute("guard", NONE)
+                    }
+                };
+                Ok(Response::new().add_event(event))
+            }
+            SelfTx::EnableModule { module } => {
+                MODULES.save(deps.storage, &module, &true)?;
+                Ok(Response::new().add_event(
+                    event(MODULE_EVENT, "enable")
+                        .add_attribute("module", module)
+                        .add_attribute("tx_id", tx_id.to_string()),
+                ))
+            }
+            SelfTx::DisableModule { module } => {
+                if !MODULES.has(deps.storage, &module) {
+                    return Err(ContractError::NonExistentModule(module.to_string()));
+                }
+                MODULES.remove(deps.storage, &module);
+                Ok(Response::new().add_event(
+                    event(MODULE_EVENT, "disable")
+                        .add_attribute("module", module)
+                        .add_attribute("tx_id", tx_id.to_string()),
+                ))
+            }
+            SelfTx::AddHook { hook } => {
+                HOOKS.save(deps.storage, &hook, &true)?;
+                Ok(Response::new().add_event(
+                    event(HOOK_EVENT, "add")
+                        .add_attribute("hook", hook)
+                        .add_attribute("tx_id", tx_id.to_string()),
+                ))
+            }
+            SelfTx::RemoveHook { hook } => {
+                if !HOOKS.has(deps.storage, &hook) {
+                    return Err(ContractError::NonExistentHook(hook.to_string()));
+                }
+                HOOKS.remove(deps.storage, &hook);
+                Ok(Response::new().add_event(
+                    event(HOOK_EVENT, "remove")
+                        .add_attribute("hook", hook)
+                        .add_attribute("tx_id", tx_id.to_string()),
+                ))
+            }
+            SelfTx::SetAllowlistMode { enabled } => {
+                ALLOWLIST_MODE.save(deps.storage, &enabled)?;
+                Ok(Response::new().add_event(
+                    config_event("set_allowlist_mode", tx_id)
+                        .add_attribute("enabled", enabled.to_string()),
+                ))
+            }
+            SelfTx::AllowRecipients { addrs } => {
+                for addr in &addrs {
+                    ALLOWLIST.save(deps.storage, addr, &true)?;
+                }
+                Ok(Response::new().add_event(
+                    event(ALLOWLIST_EVENT, "allow")
+                        .add_attribute("addrs", addrs_to_string(&addrs))
+                        .add_attribute("tx_id", tx_id.to_string()),
+                ))
+            }
+            SelfTx::DisallowRecipients { addrs } => {
+                for addr in &addrs {
+                    ALLOWLIST.remove(deps.storage, addr);
+                }
+                Ok(Response::new().add_event(
+                    event(ALLOWLIST_EVENT, "disallow")
+                        .add_attribute("addrs", addrs_to_string(&addrs))
+                        .add_attribute("tx_id", tx_id.to_string()),
+                ))
+            }
+            SelfTx::CreateSchedule {
+                msg,
+                start,
+                interval,
+                count,
+            } => create_schedule(deps, tx_id, *msg, start, interval, count),
+            SelfTx::CancelSchedule { id } => cancel_schedule(deps, tx_id, id),
+            SelfTx::CreateStream {
+                recipient,
+                amount,
+                start,
+                end,
+            } => create_stream(deps, env, tx_id, recipient, amount, start, end),
+            SelfTx::CancelStream { id } => cancel_stream(deps, env, tx_id, id),
+            SelfTx::SetContact { addr, label, tags } => set_contact(deps, tx_id, addr, label, tags),
+            SelfTx::RemoveContact { addr } => remove_contact(deps, tx_id, addr),
+            SelfTx::GrantRole { addr, role } => grant_role(deps, tx_id, addr, role),
+            SelfTx::RevokeRole { addr, role } => revoke_role(deps, tx_id, addr, role),
+            SelfTx::SetThreshold {
+                category,
+                threshold,
+            } => set_threshold(deps, tx_id, category, threshold),
+            SelfTx::SetAmountTiers { denom, tiers } => set_amount_tiers(deps, tx_id, denom, tiers),
+            SelfTx::SetRateLimit { denom, limit } => set_rate_limit(deps, env, tx_id, denom, limit),
+        }
+    }
+
+    fn set_contact(
+        deps: DepsMut,
+        tx_id: u128,
+        addr: Addr,
+        label: String,
+        tags: Vec<String>,
+    ) -> Result<Response, ContractError> {
+        if label.trim().is_empty() {
+            return Err(ContractError::EmptyContactLabel);
+        }
+
+        let contact = Contact {
+            addr: addr.clone(),
+            label,
+            tags,
+        };
+        CONTACTS.save(deps.storage, &addr, &contact)?;
+
+        Ok(Response::new().add_event(
+            event(CONTACT_EVENT, "set")
+                .add_attribute("addr", addr)
+                .add_attribute("tx_id", tx_id.to_string())
+                .add_attribute("label", contact.label)
+                .add_attribute("tags", list_to_string(&contact.tags)),
+        ))
+    }
+
+    fn remove_contact(deps: DepsMut, tx_id: u128, addr: Addr) -> Result<Response, ContractError> {
+        if !CONTACTS.has(deps.storage, &addr) {
+            return Err(ContractError::NonExistentContact(addr.to_string()));
+        }
+        CONTACTS.remove(deps.storage, &addr);
+
+        Ok(Response::new().add_event(
+            event(CONTACT_EVENT, "remove")
+                .add_attribute("addr", addr)
+                .add_attribute("tx_id", tx_id.to_string()),
+        ))
+    }
+
+    /// Fails if sending `coins` would use funds reserved for streams
+    pub fn assert_unreserved(
+        deps: &DepsMut,
+        env: &Env,
+        coins: &[Coin],
+    ) -> Result<(), ContractError> {
+        for coin in coins {
+            let reserved = STREAM_RESERVED
+                .may_load(deps.storage, &coin.denom)?
+                .unwrap_or_default();
+            if reserved.is_zero() {
+                continue;
+            }
+
+            let balance = deps
+                .querier
+                .query_balance(&env.contract.address, &coin.denom)?
+                .amount;
+            let available = balance.saturating_sub(reserved);
+            if available < coin.amount {
+                return Err(ContractError::InsufficientFunds {
+                    denom: coin.denom.clone(),
+                    available,
+                    required: coin.amount,
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn execute_from_module(
+        mut deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        msgs: Vec<CosmosMsg>,
+    ) -> Result<Response, ContractError> {
+        if !MODULES.has(deps.storage, &info.sender) {
+            return Err(ContractError::Unauthorized {});
+        }
+        assert_not_paused(&deps, &env)?;
+
+        // Only messages whose funds and recipient can be checked are let through
+        let supported = msgs.iter().all(|msg| {
+            matches!(
+                msg,
+                CosmosMsg::Bank(BankMsg::Send { .. }) | CosmosMsg::Wasm(WasmMsg::Execute { .. })
+            )
+        });
+        if !supported {
+            return Err(ContractError::UnsupportedModuleMsg);
+        }
+
+        assert_msgs_recipients(&deps, &env, &msgs)?;
+        // Funds are checked all together, so splitting a payment doesn't get around a limit
+        let outflow = msgs_outflow(&msgs);
+
+        assert_unreserved(&deps, &env, &outflow)?;
+        assert_within_rate_limits(&deps, &env, &outflow)?;
+        record_outflow(&mut deps, &env, &outflow)?;
+
+        Ok(Response::new()
+            .add_event(
+                event(MODULE_EVENT, "execute")
+                    .add_attribute("module", info.sender)
+                    .add_attribute("msg_count", msgs.len().to_string())
+                    .add_attribute("amount", coins_to_string(&outflow)),
+            )
+            .add_messages(msgs))
+    }
+
+    /// Checks the recipients of the funds `msgs` send, which can't be the wallet itself.
+    /// While the allowlist or a rate limit applies, messages whose funds can't be checked
+    /// are rejected.
+    fn assert_msgs_recipients(
+        deps: &DepsMut,
+        env: &Env,
+        msgs: &[CosmosMsg],
+    ) -> Result<(), ContractError> {
+        let allowlist_mode = ALLOWLIST_MODE.load(deps.storage)?;
+        let restricted = allowlist_mode || rate_limited(deps.storage, env.block.time)?;
+        for msg in msgs {
+            if restricted && msg_unverifiable(msg) {
+                return Err(ContractError::UnverifiableMsg);
+            }
+            // A contract doesn't exist before it's instantiated, so it can't be allowlisted
+            if let CosmosMsg::Wasm(
+                WasmMsg::Instantiate { funds, .. } | WasmMsg::Instantiate2 { funds, .. },
+            ) = msg
+            {
+                if allowlist_mode && !funds.is_empty() {
+                    return Err(ContractError::UnverifiableMsg);
+                }
+            }
+            // Addresses of other chains can't be validated, they are allowlisted as they are
+            if let CosmosMsg::Ibc(IbcMsg::Transfer { to_address, .. }) = msg {
+                assert_allowed_recipient(deps, &Addr::unchecked(to_address))?;
+            }
+            if let Some((recipient, _)) = cw20_transfer(msg) {
+                assert_allowed_recipient(deps, &deps.api.addr_validate(&recipient)?)?;
+            }
+        }
+
+        for (recipient, _) in msgs.iter().filter_map(msg_funds) {
+            let recipient = deps.api.addr_validate(recipient)?;
+            if recipient == env.contract.address {
+                return Err(ContractError::SelfCall);
+            }
+            assert_allowed_recipient(deps, &recipient)?;
+        }
+        Ok(())
+    }
+
+    /// Asks the guard whether `tx` may be executed, changing the guard is never checked
+    fn check_guard(deps: &DepsMut, tx: &Transaction, executor: &Addr) -> Result<(), ContractError> {
+        let Some(guard) = GUARD.may_load(deps.storage)? else {
+            return Ok(());
+        };
+        if matches!(tx.tx_msg, TxMsg::TxSelf(SelfTx::SetGuard { .. })) {
+            return Ok(());
+        }
+
+        let mut signers = vec![];
+        for ballot in BALLOTS
+            .prefix(tx.id)
+            .range(deps.storage, None, None, Order::Ascending)
+        {
+            let (signer, ballot) = ballot?;
+            if ballot.vote == Vote::Yes {
+                signers.push(signer);
+            }
+        }
+
+        let resp: GuardCheckResp = deps.querier.query_wasm_smart(
+            guard,
+            &GuardQueryMsg::CheckTransaction {
+                tx: tx.clone(),
+                signers,
+                executor: executor.clone(),
+            },
+        )?;
+        if !resp.allowed {
+            return Err(ContractError::GuardRejected {
+                reason: resp.reason.unwrap_or_default(),
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Message telling the guard how the execution of `tx` went
+    fn guard_notify(deps: &DepsMut, tx: &Transaction, success: bool) -> StdResult<Option<WasmMsg>> {
+        let Some(guard) = GUARD.may_load(deps.storage)? else {
+            return Ok(None);
+        };
+        if matches!(tx.tx_msg, TxMsg::TxSelf(SelfTx::SetGuard { .. })) {
+            return Ok(None);
+        }
+
+        Ok(Some(WasmMsg::Execute {
+            contract_addr: guard.to_string(),
+            msg: to_json_binary(&GuardExecuteMsg::AfterExecution {
+                tx: tx.clone(),
+                success,
+            })?,
+            funds: vec![],
+        }))
+    }
+
+    /// An empty spend limit lets the grantee spend without limit, which would get around
+    /// the rate limits
+    fn assert_limited_grant(
+        deps: &DepsMut,
+        env: &Env,
+        spend_limit: &[Coin],
+    ) -> Result<(), ContractError> {
+        if spend_limit.is_empty() && rate_limited(deps.storage, env.block.time)? {
+            return Err(ContractError::UnlimitedGrant);
+        }
+
+        Ok(())
+    }
+
+    /// While the allowlist mode is enabled funds can only go to allowlisted recipients
+    pub fn assert_allowed_recipient(deps: &DepsMut, recipient: &Addr) -> Result<(), ContractError> {
+        if ALLOWLIST_MODE.load(deps.storage)? && !ALLOWLIST.has(deps.storage, recipient) {
+            return Err(ContractError::RecipientNotAllowed(recipient.to_string()));
+        }
+
+        Ok(())
+    }
+
+    /// Outflows are capped per denom over a rolling window
+    pub fn assert_within_rate_limits(
+        deps: &DepsMut,
+        env: &Env,
+        coins: &[Coin],
+    ) -> Result<(), ContractError> {
+        for coin in coins {
+            let Some(limit) = rate_limit(deps.storage, env.block.time, &coin.denom)? else {
+                continue;
+            };
+
+            let used = outflow(deps.storage, env.block.time, &coin.denom, limit.window)?;
+            let available = limit.amount.saturating_sub(used);
+            if available < coin.amount {
+                return Err(ContractError::RateLimitExceeded {
+                    denom: coin.denom.clone(),
+                    available,
+                    required: coin.amount,
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Only rate limited denoms are tracked, outflows older than the window are dropped
+    fn record_outflow(deps: &mut DepsMut, env: &Env, coins: &[Coin]) -> StdResult<()> {
+        for coin in coins {
+            let Some(limit) = rate_limit(deps.storage, env.block.time, &coin.denom)? else {
+                continue;
+            };
+
+            let since = env.block.time.minus_seconds(limit.window);
+            let mut outflows = OUTFLOWS
+                .may_load(deps.storage, &coin.denom)?
+                .unwrap_or_default();
+            outflows.retain(|outflow| outflow.at > since);
+            outflows.push(Outflow {
+                amount: coin.amount,
+                at: env.block.time,
+            });
+            OUTFLOWS.save(deps.storage, &coin.denom, &outflows)?;
+        }
+
+        Ok(())
+    }
+
+    /// A stricter limit applies right away, anything that lets more funds out waits for
+    /// the rate limit delay so the owners can react to a compromised quorum
+    fn set_rate_limit(
+        deps: DepsMut,
+        env: Env,
+        tx_id: u128,
+        denom: String,
+        limit: Option<RateLimit>,
+    ) -> Result<Response, ContractError> {
+        if limit.as_ref().is_some_and(|limit| limit.window == 0) {
+            return Err(ContractError::InvalidRateLimit);
+        }
+
+        // A newer change replaces the one waiting for its timelock
+        let current = rate_limit(deps.storage, env.block.time, &denom)?;
+        PENDING_RATE_LIMITS.remove(deps.storage, &denom);
+
+        let immediate = match (&current, &limit) {
+            (None, _) => true,
+            (Some(_), None) => false,
+            (Some(current), Some(limit)) => limit.is_stricter_than(current),
+        };
+
+        let effective_at = if immediate {
+            match &limit {
+                Some(limit) => RATE_LIMITS.save(deps.storage, &denom, limit)?,
+                None => RATE_LIMITS.remove(deps.storage, &denom),
+            }
+            env.block.time
+        } else {
+            if let Some(current) = &current {
+                RATE_LIMITS.save(deps.storage, &denom, current)?;
+            }
+            let effective_at = env
+                .block
+                .time
+                .plus_seconds(RATE_LIMIT_DELAY.load(deps.storage)?);
+            PENDING_RATE_LIMITS.save(
+                deps.storage,
+                &denom,
+                &PendingRateLimit {
+                    limit: limit.clone(),
+                    effective_at,
+                },
+            )?;
+            effective_at
+        };
+
+        Ok(Response::new().add_event(
+            config_event("set_rate_limit", tx_id)
+                .add_attribute("denom", denom)
+                .add_attribute(
+                    "amount",
+                    limit
+                        .as_ref()
+                        .map_or(NONE.to_string(), |limit| limit.amount.to_string()),
+                )
+                .add_attribute(
+                    "window",
+                    limit
+                        .as_ref()
+                        .map_or(NONE.to_string(), |limit| limit.window.to_string()),
+                )
+                .add_attribute("effective_at", effective_at.to_string()),
+        ))
+    }
+
+    fn release_reserved(deps: &mut DepsMut, denom: &str, amount: Uint128) -> StdResult<()> {
+        let reserved = STREAM_RESERVED.load(deps.storage, denom)? - amount;
+        if reserved.is_zero() {
+            STREAM_RESERVED.remove(deps.storage, denom);
+            Ok(())
+        } else {
+            STREAM_RESERVED.save(deps.storage, denom, &reserved)
+        }
+    }
+
+    fn create_stream(
+        deps: DepsMut,
+        env: Env,
+        tx_id: u128,
+        recipient: Addr,
+        amount: Coin,
+        start: Timestamp,
+        end: Timestamp,
+    ) -> Result<Response, ContractError> {
+        if amount.amount.is_zero() || end <= start {
+            return Err(ContractError::InvalidStream);
+        }
+
+        // The whole amount is reserved upfront, so it has to be available now
+        let reserved = STREAM_RESERVED
+            .may_load(deps.storage, &amount.denom)?
+            .unwrap_or_default();
+        let balance = deps
+            .querier
+            .query_balance(&env.contract.address, &amount.denom)?
+            .amount;
+        let available = balance.saturating_sub(reserved);
+        if available < amount.amount {
+            return Err(ContractError::InsufficientFunds {
+                denom: amount.denom,
+                available,
+                required: amount.amount,
+            });
+        }
+
+        STREAM_RESERVED.update(deps.storage, &amount.denom, |reserved| -> StdResult<_> {
+            Ok(reserved.unwrap_or_default() + amount.amount)
+        })?;
+
+        let id = STREAM_COUNTER.load(deps.storage)? + 1;
+        STREAM_COUNTER.save(deps.storage, &id)?;
+
+        let stream = Stream {
+            id,
+            recipient,
+            amount,
+            start,
+            end,
+            claimed: Uint128::zero(),
+        };
+        STREAMS.save(deps.storage, id, &stream)?;
+
+        Ok(Response::new().add_event(
+            event(STREAM_EVENT, "create")
+                .add_attribute("stream_id", id.to_string())
+                .add_attribute("tx_id", tx_id.to_string())
+                .add_attribute("recipient", &stream.recipient)
+                .add_attribute("amount", stream.amount.to_string())
+                .add_attribute("start", start.to_string())
+                .add_attribute("end", end.to_string()),
+        ))
+    }
+
+    pub fn claim_stream(
+        mut deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        id: u64,
+        amount: Option<Uint128>,
+    ) -> Result<Response, ContractError> {
+        assert_not_paused(&deps, &env)?;
+
+        let mut stream = STREAMS
+            .may_load(deps.storage, id)?
+            .ok_or(ContractError::NonExistentStream(id))?;
+
+        if stream.recipient != info.sender {
+            return Err(ContractError::Unauthorized {});
+        }
+        assert_allowed_recipient(&deps, &stream.recipient)?;
+
+        let claimable = stream.claimable(env.block.time);
+        let claimable = amount.map_or(claimable, |amount| amount.min(claimable));
+        if claimable.is_zero() {
+            return Err(ContractError::NothingToClaim(id));
+        }
+
+        stream.claimed += claimable;
+        release_reserved(&mut deps, &stream.amount.denom, claimable)?;
+        if stream.claimed == stream.amount.amount {
+            STREAMS.remove(deps.storage, id);
+        } else {
+            STREAMS.save(deps.storage, id, &stream)?;
+        }
+
+        let payout = Coin {
+            denom: stream.amount.denom.clone(),
+            amount: claimable,
+        };
+        assert_within_rate_limits(&deps, &env, std::slice::from_ref(&payout))?;
+        record_outflow(&mut deps, &env, std::slice::from_ref(&payout))?;
+
+        Ok(Response::new()
+            .add_event(
+                event(STREAM_EVENT, "claim")
+                    .add_attribute("stream_id", id.to_string())
+                    .add_attribute("recipient", &stream.recipient)
+                    .add_attribute("amount", payout.to_string())
+                    .add_attribute("claimed", stream.claimed.to_string()),
+            )
+            .add_message(BankMsg::Send {
+                to_address: stream.recipient.to_string(),
+                amount: vec![payout],
+            }))
+    }
+
+    fn cancel_stream(
+        mut deps: DepsMut,
+        env: Env,
+        tx_id: u128,
+        id: u64,
+    ) -> Result<Response, ContractError> {
+        let stream = STREAMS
+            .may_load(deps.storage, id)?
+            .ok_or(ContractError::NonExistentStream(id))?;
+        STREAMS.remove(deps.storage, id);
+
+        // What vested so far still belongs to the recipient, the rest goes back to the treasury.
+        // A recipient that is no longer allowed doesn't get the vested part either.
+        let remaining = stream.amount.amount - stream.claimed;
+        release_reserved(&mut deps, &stream.amount.denom, remaining)?;
+
+        let claimable = match assert_allowed_recipient(&deps, &stream.recipient) {
+            Ok(()) => stream.vested(env.block.time) - stream.claimed,
+            Err(_) => Uint128::zero(),
+        };
+        let payout = Coin {
+            denom: stream.amount.denom.clone(),
+            amount: claimable,
+        };
+        let returned = Coin {
+            denom: stream.amount.denom.clone(),
+            amount: remaining - claimable,
+        };
+
+        let response = Response::new().add_event(
+            event(STREAM_EVENT, "cancel")
+                .add_attribute("stream_id", id.to_string())
+                .add_attribute("tx_id", tx_id.to_string())
+                .add_attribute("recipient", &stream.recipient)
+                .add_attribute("amount", payout.to_string())
+                .add_attribute("claimed", stream.claimed.to_string())
+                .add_attribute("returned", returned.to_string()),
+        );
+
+        if claimable.is_zero() {
+            return Ok(response);
+        }
+
+        // The payout counts towards the rate limit but can't block the cancellation
+        record_outflow(&mut deps, &env, std::slice::from_ref(&payout))?;
+
+        Ok(response.add_message(BankMsg::Send {
+            to_address: stream.recipient.to_string(),
+            amount: vec![payout],
+        }))
+    }
+
+    fn create_schedule(
+        deps: DepsMut,
+        tx_id: u128,
+        msg: TxMsg,
+        start: Timestamp,
+        interval: u64,
+        count: u32,
+    ) -> Result<Response, ContractError> {
+        let TxMsg::TxBank { coins, .. } = &msg else {
+            return Err(ContractError::UnschedulableMsg);
+        };
+
+        if interval == 0 || count == 0 || !payable_coins(coins) {
+            return Err(ContractError::InvalidSchedule);
+        }
+
+        let id = SCHEDULE_COUNTER.load(deps.storage)? + 1;
+        SCHEDULE_COUNTER.save(deps.storage, &id)?;
+
+        let schedule = Schedule {
+            id,
+            msg,
+            start,
+            interval,
+            count,
+            executed: 0,
+        };
+        SCHEDULES.save(deps.storage, id, &schedule)?;
+
+        Ok(Response::new().add_event(
+            event(SCHEDULE_EVENT, "create")
+                .add_attribute("schedule_id", id.to_string())
+                .add_attribute("tx_id", tx_id.to_string())
+                .add_attribute("description", schedule.msg.to_string())
+                .add_attribute("start", start.to_string())
+                .add_attribute("interval", interval.to_string())
+                .add_attribute("payments", count.to_string()),
+        ))
+    }
+
+    fn cancel_schedule(deps: DepsMut, tx_id: u128, id: u64) -> Result<Response, ContractError> {
+        let schedule = SCHEDULES
+            .may_load(deps.storage, id)?
+            .ok_or(ContractError::NonExistentSchedule(id))?;
+        SCHEDULES.remove(deps.storage, id);
+
+        Ok(Response::new().add_event(
+            event(SCHEDULE_EVENT, "cancel")
+                .add_attribute("schedule_id", id.to_string())
+                .add_attribute("tx_id", tx_id.to_string())
+                .add_attribute(
+                    "remaining",
+                    (schedule.count - schedule.executed).to_string(),
+                ),
+        ))
+    }
+
+    pub fn run_due_schedules(mut deps: DepsMut, env: Env) -> Result<Response, ContractError> {
+        assert_not_paused(&deps, &env)?;
+
+        let due: Vec<(Schedule, u32)> = SCHEDULES
+            .range(deps.storage, None, None, Order::Ascending)
+            .map(|item| item.map(|(_, schedule)| schedule))
+            .filter_map(|schedule| match schedule {
+                Ok(schedule) => {
+                    let payments = schedule.due(env.block.time);
+                    (payments > 0).then_some(Ok((schedule, payments)))
+                }
+                Err(err) => Some(Err(err)),
+            })
+            .collect::<StdResult<_>>()?;
+
+        // A schedule that can't be paid is skipped so it doesn't hold up the others, and
+        // only the payments that fit in the funds and rate limits left are made
+        let mut spent: BTreeMap<String, Uint128> = BTreeMap::new();
+        let mut response = Response::new();
+        for (mut schedule, due_payments) in due {
+            let TxMsg::TxBank { to, coins } = &schedule.msg else {
+                response =
+                    response.add_event(skip_event(&schedule, ContractError::UnschedulableMsg));
+                continue;
+            };
+            if !payable_coins(coins) {
+                response =
+                    response.add_event(skip_event(&schedule, ContractError::InvalidSchedule));
+                continue;
+            }
+            if let Err(err) = assert_allowed_recipient(&deps, to) {
+                response = response.add_event(skip_event(&schedule, err));
+                continue;
+            }
+            let payments = payable(&deps, &env, coins, due_payments, &spent)?;
+            if payments == 0 {
+                response =
+                    response.add_event(skip_event(&schedule, ContractError::ScheduleLimited));
+                continue;
+            }
+
+            // Payments that piled up since the last run are sent at once
+            let amount: Vec<Coin> = coins
+                .iter()
+                .map(|coin| Coin {
+                    denom: coin.denom.clone(),
+                    amount: coin.amount * Uint128::from(payments),
+                })
+                .collect();
+            record_outflow(&mut deps, &env, &amount)?;
+            for coin in &amount {
+                *spent.entry(coin.denom.clone()).or_default() += coin.amount;
+            }
+
+            schedule.executed += payments;
+            let remaining = schedule.count - schedule.executed;
+            if remaining == 0 {
+                SCHEDULES.remove(deps.storage, schedule.id);
+            } else {
+                SCHEDULES.save(deps.storage, schedule.id, &schedule)?;
+            }
+
+            response = response
+                .add_event(
+                    event(SCHEDULE_EVENT, "run")
+                        .add_attribute("schedule_id", schedule.id.to_string())
+                        .add_attribute("to", to)
+                        .add_attribute("amount", coins_to_string(&amount))
+                        .add_attribute("payments", payments.to_string())
+                        .add_attribute("remaining", remaining.to_string()),
+                )
+                .add_message(BankMsg::Send {
+                    to_address: to.to_string(),
+                    amount,
+                });
+        }
+
+        Ok(response)
+    }
+
+    /// A bank send fails without coins or with a zero amount
+    fn payable_coins(coins: &[Coin]) -> bool {
+        !coins.is_empty() && coins.iter().all(|coin| !coin.amount.is_zero())
+    }
+
+    fn skip_event(schedule: &Schedule, reason: ContractError) -> Event {
+        event(SCHEDULE_EVENT, "skip")
+            .add_attribute("schedule_id", schedule.id.to_string())
+            .add_attribute("reason", reason.to_string())
+    }
+
+    /// Number of the `payments` of `coins` that the unreserved funds and the rate limits
+    /// can cover, `spent` holds what earlier schedules of the same run already send
+    fn payable(
+        deps: &DepsMut,
+        env: &Env,
+        coins: &[Coin],
+        payments: u32,
+        spent: &BTreeMap<String, Uint128>,
+    ) -> StdResult<u32> {
+        let mut payable = payments;
+        for coin in coins.iter().filter(|coin| !coin.amount.is_zero()) {
+            let balance = deps
+                .querier
+                .query_balance(&env.contract.address, &coin.denom)?
+                .amount;
+            let reserved = STREAM_RESERVED
+                .may_load(deps.storage, &coin.denom)?
+                .unwrap_or_default();
+            let mut available = balance
+                .saturating_sub(reserved)
+                .saturating_sub(spent.get(&coin.denom).copied().unwrap_or_default());
+            if let Some(limit) = rate_limit(deps.storage, env.block.time, &coin.denom)? {
+                let used = outflow(deps.storage, env.block.time, &coin.denom, limit.window)?;
+                available = available.min(limit.amount.saturating_sub(used));
+            }
+
+            let fits = u32::try_from((available / coin.amount).u128()).unwrap_or(u32::MAX);
+            payable = payable.min(fits);
+        }
+
+        Ok(payable)
+    }
+
+    /// Owners hold every role, other addresses only the roles granted to them
+    pub fn assert_role(
+        deps: &DepsMut,
+        info: &MessageInfo,
+        role: Role,
+    ) -> Result<(), ContractError> {
+        if member_weight(deps.as_ref(), &info.sender)?.is_some() {
+            return Ok(());
+        }
+
+        let roles = ROLES
+            .may_load(deps.storage, &info.sender)?
+            .unwrap_or_default();
+        if role == Role::Signer || !roles.contains(&role) {
+            return Err(ContractError::Unauthorized {});
+        }
+
+        Ok(())
+    }
+
+    fn grant_role(
+        deps: DepsMut,
+        tx_id: u128,
+        addr: Addr,
+        role: Role,
+    ) -> Result<Response, ContractError> {
+        if role == Role::Signer {
+            return Err(ContractError::SignerRoleIsOwnership);
+        }
+
+        let mut roles = ROLES.may_load(deps.storage, &addr)?.unwrap_or_default();
+        if !roles.contains(&role) {
+            roles.push(role.clone());
+            ROLES.save(deps.storage, &addr, &roles)?;
+        }
+
+        Ok(Response::new().add_event(
+            event(ROLE_EVENT, "grant")
+                .add_attribute("addr", addr)
+                .add_attribute("role", role.to_string())
+                .add_attribute("tx_id", tx_id.to_string()),
+        ))
+    }
+
+    fn revoke_role(
+        deps: DepsMut,
+        tx_id: u128,
+        addr: Addr,
+        role: Role,
+    ) -> Result<Response, ContractError> {
+        if role == Role::Signer {
+            return Err(ContractError::SignerRoleIsOwnership);
+        }
+
+        let mut roles = ROLES.may_load(deps.storage, &addr)?.unwrap_or_default();
+        let index = roles
+            .iter()
+            .position(|granted| *granted == role)
+            .ok_or_else(|| ContractError::RoleNotGranted {
+                addr: addr.to_string(),
+                role: role.clone(),
+            })?;
+        roles.remove(index);
+
+        if roles.is_empty() {
+            ROLES.remove(deps.storage, &addr);
+        } else {
+            ROLES.save(deps.storage, &addr, &roles)?;
+        }
+
+        Ok(Response::new().add_event(
+            event(ROLE_EVENT, "revoke")
+                .add_attribute("addr", addr)
+                .add_attribute("role", role.to_string())
+                .add_attribute("tx_id", tx_id.to_string()),
+        ))
+    }
+
+    pub fn assert_not_paused(deps: &DepsMut, env: &Env) -> Result<(), ContractError> {
+        if let Some(pause) = PAUSE.may_load(deps.storage)? {
+            if env.block.time < pause.expires_at {
+                return Err(ContractError::Paused {
+                    until: pause.expires_at,
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn pause(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response, ContractError> {
+        if let Some(pause) = PAUSE.may_load(deps.storage)? {
+            if env.block.time < pause.expires_at {
+                return Err(ContractError::AlreadyPaused {
+                    until: pause.expires_at,
+                });
+            }
+        }
+        // A pause is followed by a cooldown as long as the pause, so owners can't take
+        // turns to keep the wallet frozen and the others get time to remove them
+        if let Some(until) = PAUSE_COOLDOWN.may_load(deps.storage)? {
+            if env.block.time < until {
+                return Err(ContractError::PauseCooldown { until });
+            }
+        }
+
+        let duration = PAUSE_DURATION.load(deps.storage)?;
+        let pause = PauseInfo {
+            paused_by: info.sender,
+            expires_at: env.block.time.plus_seconds(duration),
+        };
+        PAUSE.save(deps.storage, &pause)?;
+        PAUSE_COOLDOWN.save(deps.storage, &pause.expires_at.plus_seconds(duration))?;
+
+        Ok(Response::new().add_event(
+            event(PAUSE_EVENT, "pause")
+                .add_attribute("actor", pause.paused_by)
+                .add_attribute("expires_at", pause.expires_at.to_string()),
+        ))
+    }
+
+    fn unpause(deps: DepsMut, env: Env, tx_id: u128) -> Result<Response, ContractError> {
+        PAUSE.remove(deps.storage);
+        let duration = PAUSE_DURATION.load(deps.storage)?;
+        PAUSE_COOLDOWN.save(deps.storage, &env.block.time.plus_seconds(duration))?;
+        Ok(Response::new()
+            .add_event(event(PAUSE_EVENT, "unpause").add_attribute("tx_id", tx_id.to_string())))
+    }
+
+    pub fn is_guardian(deps: &DepsMut, info: &MessageInfo) -> Result<(), ContractError> {
+        let guardians = GUARDIANS
+            .may_load(deps.storage)?
+            .ok_or(ContractError::NoGuardians)?;
+        if !guardians.guardians.contains(&info.sender) {
+            return Err(ContractError::Unauthorized {});
+        }
+
+        Ok(())
+    }
+
+    pub fn validate_guardians(guardians: &GuardianSet) -> Result<(), ContractError> {
+        if guardians.guardians.is_empty() {
+            return Err(ContractError::ZeroGuardians);
+        }
+
+        if guardians.threshold == 0 || guardians.threshold > guardians.guardians.len() as u32 {
+            return Err(ContractError::WrongGuardianThreshold {
+                threshold: guardians.threshold,
+                guardians: guardians.guardians.len() as u32,
+            });
+        }
+
+        Ok(())
+    }
+
+    pub fn propose_recovery(
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        owners: Vec<Addr>,
+        quorum: u32,
+    ) -> Result<Response, ContractError> {
+        assert_not_group_managed(&deps)?;
+        if RECOVERY.may_load(deps.storage)?.is_some() {
+            return Err(ContractError::RecoveryInProgress);
+        }
+
+        if owners.is_empty() {
+            return Err(ContractError::ZeroOwners);
+        }
+
+        if quorum > owners.len() as u32 {
+            return Err(ContractError::WrongQuorum {
+                quorum,
+                owners: owners.len() as u32,
+            });
+        }
+
+        // Like proposing a transaction, proposing a recovery counts as the guardian's approval
+        let recovery = Recovery {
+            owners,
+            quorum,
+            approvals: vec![info.sender.clone()],
+            proposed_at: env.block.time,
+        };
+        RECOVERY.save(deps.storage, &recovery)?;
 
-    pub fn make_next_id(deps: DepsMut) -> StdResult<u128> {
-        let mut tx_counter = TX_COUNTER.load(deps.storage)?;
-        tx_counter += 1;
-        TX_COUNTER.save(deps.storage, &tx_counter)?;
-        Ok(tx_counter)
+        Ok(Response::new().add_event(
+            event(RECOVERY_EVENT, "propose")
+                .add_attribute("actor", info.sender)
+                .add_attribute("owners", addrs_to_string(&recovery.owners))
+                .add_attribute("quorum", recovery.quorum.to_string()),
+        ))
     }
 
-    pub fn create_tx(
-        mut deps: DepsMut,
-        info: MessageInfo,
-        tx_msg: TxMsg,
-    ) -> Result<Response, ContractError> {
-        let admins = ADMINS.load(deps.storage)?;
+    pub fn approve_recovery(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+        let mut recovery = RECOVERY
+            .may_load(deps.storage)?
+            .ok_or(ContractError::NoRecovery)?;
 
-        if !admins.contains(&info.sender) {
-            return Err(ContractError::Unauthorized {});
+        if recovery.approvals.contains(&info.sender) {
+            return Err(ContractError::AlreadyApprovedRecovery);
         }
 
-        let mut pending_txs = PENDING_TXS.load(deps.storage)?;
-        let next_id = make_next_id(deps.branch())?;
-        let mut tx = Transaction::new(tx_msg, next_id);
-
-        tx.num_confirmations = 1;
-        pending_txs.push(tx.clone());
-        PENDING_TXS.save(deps.storage, &pending_txs)?;
+        recovery.approvals.push(info.sender.clone());
+        RECOVERY.save(deps.storage, &recovery)?;
 
-        // Since the user proposed the tx he already approves that it will be executed,
-        // This way he won't have to approve the transaction again
-        SIGNED_TX.save(deps.storage, (info.sender, next_id), &true)?;
-        Ok(Response::new().add_event(Event::new("new_tx").add_attribute("tx", tx.to_string())))
+        Ok(Response::new()
+            .add_event(event(RECOVERY_EVENT, "approve").add_attribute("actor", info.sender)))
     }
 
-    pub fn sign_tx(
+    pub fn execute_recovery(
         deps: DepsMut,
+        env: Env,
         info: MessageInfo,
-        tx_id: u128,
     ) -> Result<Response, ContractError> {
-        if let Ok(signed) = SIGNED_TX.load(deps.storage, (info.sender.clone(), tx_id)) {
-            if signed {
-                return Err(ContractError::AlreadySigned(tx_id));
-            }
-        }
-
-        SIGNED_TX.save(deps.storage, (info.sender, tx_id), &true)?;
+        let recovery = RECOVERY
+            .may_load(deps.storage)?
+            .ok_or(ContractError::NoRecovery)?;
+        let guardians = GUARDIANS.load(deps.storage)?;
 
-        let mut pending_txs = PENDING_TXS.load(deps.storage)?;
+        // Guardians removed since they approved don't count anymore
+        let approvals = recovery
+            .approvals
+            .iter()
+            .filter(|guardian| guardians.guardians.contains(guardian))
+            .count() as u32;
 
-        let tx = pending_txs
-            .find_mut(tx_id)
-            .ok_or(ContractError::NonExistentTx(tx_id))?;
+        if approvals < guardians.threshold {
+            return Err(ContractError::NotEnoughGuardianApprovals {
+                threshold: guardians.threshold,
+                approvals,
+            });
+        }
 
-        tx.num_confirmations += 1;
+        let ready_at = recovery.proposed_at.plus_seconds(guardians.recovery_delay);
+        if env.block.time < ready_at {
+            return Err(ContractError::RecoveryDelayNotPassed { ready_at });
+        }
 
-        PENDING_TXS.save(deps.storage, &pending_txs)?;
+        let old_owners = ADMINS.load(deps.storage)?;
+        ADMINS.save(deps.storage, &recovery.owners)?;
+        QUORUM.save(deps.storage, &recovery.quorum)?;
+        RECOVERY.remove(deps.storage);
 
-        Ok(Response::new())
-    }
+        // Roles, modules and thresholds were set by the replaced owners
+        ROLES.clear(deps.storage);
+        MODULES.clear(deps.storage);
+        THRESHOLDS.clear(deps.storage);
+        AMOUNT_TIERS.clear(deps.storage);
 
-    pub fn execute_tx(deps: DepsMut, tx_id: u128) -> Result<Response, ContractError> {
+        // Confirmations on pending transactions were given by the replaced owners, so the
+        // transactions are closed as rejected and their votes and cw3 proposals dropped
         let pending_txs = PENDING_TXS.load(deps.storage)?;
+        PENDING_TXS.save(deps.storage, &PendingTransactions::new(Vec::new()))?;
+        let mut closed = vec![];
+        for mut tx in pending_txs.0 {
+            let voters = BALLOTS
+                .prefix(tx.id)
+                .keys(deps.storage, None, None, Order::Ascending)
+                .collect::<StdResult<Vec<_>>>()?;
+            for voter in voters {
+                BALLOTS.remove(deps.storage, (tx.id, &voter));
+                SIGNED_TX.remove(deps.storage, (voter, tx.id));
+            }
+            PROPOSALS.remove(deps.storage, tx.id);
 
-        let tx = pending_txs
-            .find(tx_id)
-            .ok_or(ContractError::NonExistentTx(tx_id))?;
+            tx.status = TxStatus::Rejected;
+            closed.push(tx_event("close", &tx, &info.sender));
+            COMPLETED_TXS.update(deps.storage, |mut txs| -> StdResult<_> {
+                txs.push(tx);
+                Ok(txs)
+            })?;
+        }
 
-        let quorum = QUORUM.load(deps.storage)?;
+        let removed = old_owners
+            .iter()
+            .filter(|owner| !recovery.owners.contains(owner))
+            .map(|owner| owner_event("remove", owner));
+        let added = recovery
+            .owners
+            .iter()
+            .filter(|owner| !old_owners.contains(owner))
+            .map(|owner| owner_event("add", owner));
 
-        if quorum > tx.num_confirmations {
-            return Err(ContractError::NotEnoughSignatures {
-                quorum,
-                num_signed: tx.num_confirmations,
-            });
+        let diffs = old_owners
+            .iter()
+            .filter(|owner| !recovery.owners.contains(owner))
+            .map(|owner| member_diff(owner, Some(1), None))
+            .chain(
+                recovery
+                    .owners
+                    .iter()
+                    .filter(|owner| !old_owners.contains(owner))
+                    .map(|owner| member_diff(owner, None, Some(1))),
+            )
+            .collect();
+
+        Ok(Response::new()
+            .add_submessages(member_changed_msgs(deps.storage, diffs)?)
+            .add_event(
+                event(RECOVERY_EVENT, "execute")
+                    .add_attribute("actor", info.sender)
+                    .add_attribute("owners", addrs_to_string(&recovery.owners))
+                    .add_attribute("quorum", recovery.quorum.to_string()),
+            )
+            .add_events(removed)
+            .add_events(added)
+            .add_events(closed))
+    }
+
+    pub fn veto_recovery(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+        if RECOVERY.may_load(deps.storage)?.is_none() {
+            return Err(ContractError::NoRecovery);
         }
 
-        PENDING_TXS.update(deps.storage, |mut txs|->StdResult<PendingTransactions>{
-            txs.remove(&tx.clone());
-            Ok(txs)
-        })?;
+        RECOVERY.remove(deps.storage);
 
-        COMPLETED_TXS.update(deps.storage, |mut txs: CompletedTransactions|-> StdResult<CompletedTransactions>{
-            txs.push(tx.clone());
-            Ok(txs)
-        })?;
+        Ok(Response::new()
+            .add_event(event(RECOVERY_EVENT, "veto").add_attribute("actor", info.sender)))
+    }
 
-        let response = match tx.tx_msg.clone() {
-            TxMsg::TxBank { to, coins } => {
-                let message = BankMsg::Send {
-                    to_address: to.to_string(),
-                    amount: coins.clone(),
-                };
-                Ok(Response::new().add_message(message))
+    fn update_guardians(
+        deps: DepsMut,
+        tx_id: u128,
+        guardians: Option<GuardianSet>,
+    ) -> Result<Response, ContractError> {
+        let event = config_event("update_guardians", tx_id);
+        let event = match guardians {
+            Some(guardians) => {
+                validate_guardians(&guardians)?;
+                GUARDIANS.save(deps.storage, &guardians)?;
+                event
+                    .add_attribute("guardians", addrs_to_string(&guardians.guardians))
+                    .add_attribute("threshold", guardians.threshold.to_string())
+                    .add_attribute("recovery_delay", guardians.recovery_delay.to_string())
+            }
+            None => {
+                GUARDIANS.remove(deps.storage);
+                event.add_attribute("guardians", NONE)
             }
-            TxMsg::TxSelf(self_msg) => match self_msg {
-                crate::state::SelfTx::AddOwner { owner, quorum } => add_owner(deps, owner, quorum),
-                crate::state::SelfTx::RemoveOwner { owner, quorum } => {
-                    remove_owner(deps, owner, quorum)
-                }
-                crate::state::SelfTx::UpdateQuorum { quorum } => update_quorum(deps, quorum),
-            },
         };
-        
-        response
-    }
 
-    pub fn is_admin(deps: &DepsMut, info: &MessageInfo) -> Result<(), ContractError> {
-        let admins = ADMINS.load(deps.storage)?;
-        if !admins.contains(&info.sender) {
-            return Err(ContractError::Unauthorized {});
-        }
+        // The owners were able to reach quorum, so any recovery in progress is obsolete
+        RECOVERY.remove(deps.storage);
 
-        Ok(())
+        Ok(Response::new().add_event(event))
     }
 
     fn add_owner(
         deps: DepsMut,
+        tx_id: u128,
         owner: Addr,
         quorum: Option<u32>,
     ) -> Result<Response, ContractError> {
+        assert_not_group_managed(&deps)?;
         let mut admins = ADMINS.load(deps.storage)?;
-        admins.push(owner);
+        admins.push(owner.clone());
 
         if let Some(quorum_val) = quorum {
             if quorum_val > admins.len() as u32 {
@@ -198,18 +2173,90 @@ mod exec {
         }
         ADMINS.save(deps.storage, &admins)?;
 
-        Ok(Response::new())
+        let diffs = vec![member_diff(&owner, None, Some(1))];
+        Ok(quorum_response(
+            owner_event("add", &owner).add_attribute("tx_id", tx_id.to_string()),
+            tx_id,
+            quorum,
+        )
+        .add_submessages(member_changed_msgs(deps.storage, diffs)?))
+    }
+
+    fn member_diff(owner: &Addr, old: Option<u64>, new: Option<u64>) -> MemberDiff {
+        MemberDiff {
+            key: owner.to_string(),
+            old,
+            new,
+        }
+    }
+
+    /// cw4 member-changed hook messages notifying every hook of `diffs`, a failing hook
+    /// can't block owner changes or a recovery
+    fn member_changed_msgs(
+        storage: &dyn Storage,
+        diffs: Vec<MemberDiff>,
+    ) -> StdResult<Vec<SubMsg>> {
+        let msg = to_json_binary(&HookExecuteMsg::MemberChangedHook { diffs })?;
+        HOOKS
+            .keys(storage, None, None, Order::Ascending)
+            .map(|hook| {
+                let msg = WasmMsg::Execute {
+                    contract_addr: hook?.to_string(),
+                    msg: msg.clone(),
+                    funds: vec![],
+                };
+                Ok(SubMsg::reply_on_error(msg, NOTIFY_HOOK_REPLY_ID))
+            })
+            .collect()
+    }
+
+    /// Owner changes that also set the quorum are reported like a quorum update
+    fn quorum_response(owner_change: Event, tx_id: u128, quorum: Option<u32>) -> Response {
+        let response = Response::new().add_event(owner_change);
+        match quorum {
+            Some(quorum) => response.add_event(
+                config_event("update_quorum", tx_id).add_attribute("quorum", quorum.to_string()),
+            ),
+            None => response,
+        }
+    }
+
+    /// Owners of a wallet backed by a cw4 group are changed in the group
+    fn assert_not_group_managed(deps: &DepsMut) -> Result<(), ContractError> {
+        match GROUP.may_load(deps.storage)? {
+            Some(group) => Err(ContractError::GroupManaged(group.to_string())),
+            None => Ok(()),
+        }
     }
 
     fn remove_owner(
         deps: DepsMut,
+        tx_id: u128,
         owner: Addr,
         quorum: Option<u32>,
     ) -> Result<Response, ContractError> {
+        assert_not_group_managed(&deps)?;
         let mut admins = ADMINS.load(deps.storage)?;
         let index = admins.iter().position(|x| *x == owner).unwrap();
         admins.remove(index);
 
+        // Categories and amount tiers can't require more signatures than there are owners
+        for category in TxCategory::ALL {
+            if let Some(threshold) = THRESHOLDS.may_load(deps.storage, category.as_str())? {
+                if threshold > admins.len() as u32 {
+                    return Err(ContractError::WrongThreshold {
+                        category,
+                        threshold,
+                        owners: admins.len() as u32,
+                    });
+                }
+            }
+        }
+        for tiers in AMOUNT_TIERS.range(deps.storage, None, None, Order::Ascending) {
+            let (denom, tiers) = tiers?;
+            validate_amount_tiers(&denom, &tiers, admins.len() as u32)?;
+        }
+
         if let Some(quorum_val) = quorum {
             if quorum_val > admins.len() as u32 {
                 return Err(ContractError::WrongQuorum {
@@ -221,42 +2268,219 @@ mod exec {
         }
 
         ADMINS.save(deps.storage, &admins)?;
-        Ok(Response::new())
+
+        let diffs = vec![member_diff(&owner, Some(1), None)];
+        Ok(quorum_response(
+            owner_event("remove", &owner).add_attribute("tx_id", tx_id.to_string()),
+            tx_id,
+            quorum,
+        )
+        .add_submessages(member_changed_msgs(deps.storage, diffs)?))
     }
 
-    fn update_quorum(deps: DepsMut, quorum: u32) -> Result<Response, ContractError> {
-        let admins = ADMINS.load(deps.storage)?;
-        if quorum > admins.len() as u32 {
-            return Err(ContractError::WrongQuorum {
-                quorum: quorum,
-                owners: admins.len() as u32,
-            });
+    fn set_threshold(
+        deps: DepsMut,
+        tx_id: u128,
+        category: TxCategory,
+        threshold: Option<u32>,
+    ) -> Result<Response, ContractError> {
+        let event =
+            config_event("set_threshold", tx_id).add_attribute("category", category.as_str());
+
+        let event = match threshold {
+            Some(threshold) => {
+                let owners = weight_to_u32(total_weight(deps.as_ref())?)?;
+                if threshold == 0 || threshold > owners {
+                    return Err(ContractError::WrongThreshold {
+                        category,
+                        threshold,
+                        owners,
+                    });
+                }
+                THRESHOLDS.save(deps.storage, category.as_str(), &threshold)?;
+                event.add_attribute("threshold", threshold.to_string())
+            }
+            None => {
+                THRESHOLDS.remove(deps.storage, category.as_str());
+                event.add_attribute("threshold", NONE)
+            }
+        };
+
+        Ok(Response::new().add_event(event))
+    }
+
+    fn set_amount_tiers(
+        deps: DepsMut,
+        tx_id: u128,
+        denom: String,
+        tiers: Vec<AmountTier>,
+    ) -> Result<Response, ContractError> {
+        if tiers.is_empty() {
+            AMOUNT_TIERS.remove(deps.storage, &denom);
+        } else {
+            let owners = weight_to_u32(total_weight(deps.as_ref())?)?;
+            validate_amount_tiers(&denom, &tiers, owners)?;
+            AMOUNT_TIERS.save(deps.storage, &denom, &tiers)?;
+        }
+
+        Ok(Response::new().add_event(
+            config_event("set_amount_tiers", tx_id)
+                .add_attribute("denom", denom)
+                .add_attribute("tiers", list_to_string(&tiers)),
+        ))
+    }
+
+    /// Bounds must be ascending and only the last tier can be unbounded, so every amount
+    /// falls in exactly one tier
+    fn validate_amount_tiers(
+        denom: &str,
+        tiers: &[AmountTier],
+        owners: u32,
+    ) -> Result<(), ContractError> {
+        let invalid = || ContractError::InvalidAmountTiers {
+            denom: denom.to_string(),
+            owners,
+        };
+
+        let (last, bounded) = tiers.split_last().ok_or_else(invalid)?;
+        if last.up_to.is_some() {
+            return Err(invalid());
+        }
+
+        let mut previous = None;
+        for tier in bounded {
+            let up_to = tier.up_to.ok_or_else(invalid)?;
+            if previous.is_some_and(|previous| up_to <= previous) {
+                return Err(invalid());
+            }
+            previous = Some(up_to);
+        }
+
+        if tiers
+            .iter()
+            .any(|tier| tier.threshold == 0 || tier.threshold > owners)
+        {
+            return Err(invalid());
+        }
+
+        Ok(())
+    }
+
+    fn update_quorum(deps: DepsMut, tx_id: u128, quorum: u32) -> Result<Response, ContractError> {
+        let owners = weight_to_u32(total_weight(deps.as_ref())?)?;
+        if quorum > owners {
+            return Err(ContractError::WrongQuorum { quorum, owners });
         }
         QUORUM.save(deps.storage, &quorum)?;
-        Ok(Response::new())
+        Ok(Response::new().add_event(
+            config_event("update_quorum", tx_id).add_attribute("quorum", quorum.to_string()),
+        ))
     }
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
         QueryMsg::ListAdmins {} => to_json_binary(&query::list_admins(deps)?),
         QueryMsg::ListPending {} => to_json_binary(&query::list_pending(deps)?),
         QueryMsg::ListSigned { admin, tx_id } => {
             to_json_binary(&query::list_signed(deps, admin, tx_id)?)
         }
-        QueryMsg::ListCompletedTxs { offset, limit } => to_json_binary(&query::list_completed_txs(deps, offset, limit)?),
-        QueryMsg::GetQuorum {  } => to_json_binary(&query::get_quorum(deps)?),
+        QueryMsg::ListCompletedTxs { offset, limit } => {
+            to_json_binary(&query::list_completed_txs(deps, offset, limit)?)
+        }
+        QueryMsg::GetQuorum { tx_id } => to_json_binary(&query::get_quorum(deps, tx_id)?),
+        QueryMsg::ListModules { start_after, limit } => {
+            to_json_binary(&query::list_modules(deps, start_after, limit)?)
+        }
+        QueryMsg::ListAllowlist { start_after, limit } => {
+            to_json_binary(&query::list_allowlist(deps, start_after, limit)?)
+        }
+        QueryMsg::GetRateLimit { denom } => {
+            to_json_binary(&query::get_rate_limit(deps, env, denom)?)
+        }
+        QueryMsg::GetAmountTiers { denom } => {
+            to_json_binary(&query::get_amount_tiers(deps, denom)?)
+        }
+        QueryMsg::GetGuardians {} => to_json_binary(&query::get_guardians(deps)?),
+        QueryMsg::GetRecovery {} => to_json_binary(&query::get_recovery(deps)?),
+        QueryMsg::GetPauseState {} => to_json_binary(&query::get_pause_state(deps, env)?),
+        QueryMsg::GetConfig {} => to_json_binary(&query::get_config(deps)?),
+        QueryMsg::GetSchedule { id } => to_json_binary(&query::get_schedule(deps, id)?),
+        QueryMsg::ListSchedules { start_after, limit } => {
+            to_json_binary(&query::list_schedules(deps, start_after, limit)?)
+        }
+        QueryMsg::GetStream { id } => to_json_binary(&query::get_stream(deps, env, id)?),
+        QueryMsg::ListStreams { start_after, limit } => {
+            to_json_binary(&query::list_streams(deps, env, start_after, limit)?)
+        }
+        QueryMsg::GetReserved {} => to_json_binary(&query::get_reserved(deps)?),
+        QueryMsg::GetContact { addr } => to_json_binary(&query::get_contact(deps, addr)?),
+        QueryMsg::ListContacts { start_after, limit } => {
+            to_json_binary(&query::list_contacts(deps, start_after, limit)?)
+        }
+        QueryMsg::GetThresholds {} => to_json_binary(&query::get_thresholds(deps)?),
+        QueryMsg::GetRoles { addr } => to_json_binary(&query::get_roles(deps, addr)?),
+        QueryMsg::ListRoles { start_after, limit } => {
+            to_json_binary(&query::list_roles(deps, start_after, limit)?)
+        }
+        QueryMsg::Threshold {} => to_json_binary(&query::threshold(deps)?),
+        QueryMsg::Proposal { proposal_id } => {
+            to_json_binary(&query::proposal(deps, env, proposal_id)?)
+        }
+        QueryMsg::ListProposals { start_after, limit } => {
+            to_json_binary(&query::list_proposals(deps, env, start_after, limit)?)
+        }
+        QueryMsg::ReverseProposals {
+            start_before,
+            limit,
+        } => to_json_binary(&query::reverse_proposals(deps, env, start_before, limit)?),
+        QueryMsg::Vote { proposal_id, voter } => {
+            to_json_binary(&query::vote(deps, proposal_id, voter)?)
+        }
+        QueryMsg::ListVotes {
+            proposal_id,
+            start_after,
+            limit,
+        } => to_json_binary(&query::list_votes(deps, proposal_id, start_after, limit)?),
+        QueryMsg::Voter { address } => to_json_binary(&query::voter(deps, address)?),
+        QueryMsg::ListVoters { start_after, limit } => {
+            to_json_binary(&query::list_voters(deps, start_after, limit)?)
+        }
+        QueryMsg::Member { addr, .. } => to_json_binary(&query::member(deps, addr)?),
+        QueryMsg::ListMembers { start_after, limit } => {
+            to_json_binary(&query::list_members(deps, start_after, limit)?)
+        }
+        QueryMsg::TotalWeight { .. } => to_json_binary(&TotalWeightResp {
+            weight: total_weight(deps)?,
+        }),
+        QueryMsg::Hooks {} => to_json_binary(&query::hooks(deps)?),
     }
 }
 
 mod query {
     use super::*;
     use crate::{
-        msg::{ListAdminsResp, ListCompletedTxsResp, ListPendingResp, ListSignedResp},
-        state::{PENDING_TXS, SIGNED_TX},
+        msg::{
+            AmountTiersResp, CategoryThreshold, ConfigResp, GuardiansResp, HooksResp,
+            ListAdminsResp, ListAllowlistResp, ListCompletedTxsResp, ListContactsResp,
+            ListMembersResp, ListModulesResp, ListPendingResp, ListProposalsResp, ListRolesResp,
+            ListSchedulesResp, ListSignedResp, ListStreamsResp, ListVotersResp, ListVotesResp,
+            Member, PauseResp, ProposalResp, RateLimitResp, RecipientInfo, RecoveryResp,
+            ReservedResp, RoleMember, RolesResp, StreamResp, ThresholdsResp, VoteInfo, VoteResp,
+            VoterDetail, VoterResp,
+        },
+        state::{
+            Contact, Schedule, Stream, TxCategory, CONTACTS, PAUSE, PENDING_TXS, RECOVERY, ROLES,
+            SCHEDULES, SIGNED_TX, STREAMS, STREAM_RESERVED,
+        },
     };
-    use cosmwasm_std::Addr;
+    use cosmwasm_std::{Addr, Coin, StdError};
+    use cw_storage_plus::Bound;
+    use cw_utils::ThresholdResponse;
+
+    const DEFAULT_LIMIT: u32 = 10;
+    const MAX_LIMIT: u32 = 30;
 
     pub fn list_signed(deps: Deps, admin: Addr, tx_id: u128) -> StdResult<ListSignedResp> {
         let signed = SIGNED_TX.load(deps.storage, (admin, tx_id))?;
@@ -271,11 +2495,19 @@ mod query {
 
     pub fn list_pending(deps: Deps) -> StdResult<ListPendingResp> {
         let transactions = PENDING_TXS.load(deps.storage)?;
+        let recipients = recipients(deps, &transactions.0)?;
 
-        Ok(ListPendingResp { transactions })
+        Ok(ListPendingResp {
+            transactions,
+            recipients,
+        })
     }
 
-    pub fn list_completed_txs(deps: Deps, offset: Option<u32>, limit: Option<u32>) ->StdResult<ListCompletedTxsResp> {
+    pub fn list_completed_txs(
+        deps: Deps,
+        offset: Option<u32>,
+        limit: Option<u32>,
+    ) -> StdResult<ListCompletedTxsResp> {
         let completed_txs = COMPLETED_TXS.load(deps.storage)?;
 
         let start_pos = offset.unwrap_or(0) as usize;
@@ -294,13 +2526,460 @@ mod query {
 
         let resp = ListCompletedTxsResp {
             transactions: CompletedTransactions(paginated_txs.to_vec()), // This might require adjustment depending on your actual types and structures.
+            recipients: recipients(deps, paginated_txs)?,
         };
 
         Ok(resp)
     }
 
-    pub fn get_quorum(deps: Deps) ->StdResult<u32> {
+    pub fn get_quorum(deps: Deps, tx_id: Option<u128>) -> StdResult<u32> {
+        let tx_id = match tx_id {
+            Some(tx_id) => tx_id,
+            None => return QUORUM.load(deps.storage),
+        };
+
+        let pending_txs = PENDING_TXS.load(deps.storage)?;
+        let tx = pending_txs
+            .find(tx_id)
+            .ok_or_else(|| StdError::not_found(format!("Pending transaction {}", tx_id)))?;
+
+        required_signatures(deps.storage, &tx.tx_msg)
+    }
+
+    pub fn get_rate_limit(deps: Deps, env: Env, denom: String) -> StdResult<RateLimitResp> {
+        let now = env.block.time;
+        let limit = rate_limit(deps.storage, now, &denom)?;
+        let pending = PENDING_RATE_LIMITS
+            .may_load(deps.storage, &denom)?
+            .filter(|pending| now < pending.effective_at);
+
+        let used = match &limit {
+            Some(limit) => outflow(deps.storage, now, &denom, limit.window)?,
+            None => Uint128::zero(),
+        };
+
+        Ok(RateLimitResp {
+            available: limit
+                .as_ref()
+                .map(|limit| limit.amount.saturating_sub(used)),
+            limit,
+            pending,
+            used,
+        })
+    }
+
+    pub fn get_amount_tiers(deps: Deps, denom: String) -> StdResult<AmountTiersResp> {
+        let tiers = AMOUNT_TIERS
+            .may_load(deps.storage, &denom)?
+            .unwrap_or_default();
+        Ok(AmountTiersResp { tiers })
+    }
+
+    pub fn get_thresholds(deps: Deps) -> StdResult<ThresholdsResp> {
         let quorum = QUORUM.load(deps.storage)?;
-        Ok(quorum)
+
+        let thresholds = TxCategory::ALL
+            .into_iter()
+            .map(|category| {
+                let threshold = THRESHOLDS.may_load(deps.storage, category.as_str())?;
+                Ok(CategoryThreshold {
+                    category,
+                    threshold: threshold.unwrap_or(quorum),
+                    custom: threshold.is_some(),
+                })
+            })
+            .collect::<StdResult<_>>()?;
+
+        Ok(ThresholdsResp { thresholds })
+    }
+
+    pub fn get_guardians(deps: Deps) -> StdResult<GuardiansResp> {
+        let guardians = GUARDIANS.may_load(deps.storage)?;
+        Ok(GuardiansResp { guardians })
+    }
+
+    pub fn get_recovery(deps: Deps) -> StdResult<RecoveryResp> {
+        let recovery = RECOVERY.may_load(deps.storage)?;
+        Ok(RecoveryResp { recovery })
+    }
+
+    pub fn get_pause_state(deps: Deps, env: Env) -> StdResult<PauseResp> {
+        // An expired pause is reported the same as no pause at all
+        let pause = PAUSE
+            .may_load(deps.storage)?
+            .filter(|pause| env.block.time < pause.expires_at);
+        let cooldown_until = PAUSE_COOLDOWN
+            .may_load(deps.storage)?
+            .filter(|until| env.block.time < *until);
+
+        Ok(PauseResp {
+            paused: pause.is_some(),
+            paused_by: pause.as_ref().map(|pause| pause.paused_by.clone()),
+            expires_at: pause.map(|pause| pause.expires_at),
+            cooldown_until,
+        })
+    }
+
+    pub fn get_config(deps: Deps) -> StdResult<ConfigResp> {
+        Ok(ConfigResp {
+            quorum: QUORUM.load(deps.storage)?,
+            pause_duration: PAUSE_DURATION.load(deps.storage)?,
+            permissionless_execution: PERMISSIONLESS_EXECUTION.load(deps.storage)?,
+            auto_execute: AUTO_EXECUTE.load(deps.storage)?,
+            rate_limit_delay: RATE_LIMIT_DELAY.load(deps.storage)?,
+            allowlist_mode: ALLOWLIST_MODE.load(deps.storage)?,
+            guard: GUARD.may_load(deps.storage)?,
+            group: GROUP.may_load(deps.storage)?,
+        })
+    }
+
+    pub fn get_schedule(deps: Deps, id: u64) -> StdResult<Schedule> {
+        SCHEDULES.load(deps.storage, id)
+    }
+
+    pub fn list_schedules(
+        deps: Deps,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    ) -> StdResult<ListSchedulesResp> {
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+        let start = start_after.map(Bound::exclusive);
+
+        let schedules = SCHEDULES
+            .range(deps.storage, start, None, Order::Ascending)
+            .take(limit)
+            .map(|item| item.map(|(_, schedule)| schedule))
+            .collect::<StdResult<_>>()?;
+
+        Ok(ListSchedulesResp { schedules })
+    }
+
+    fn stream_resp(env: &Env, stream: Stream) -> StreamResp {
+        StreamResp {
+            vested: stream.vested(env.block.time),
+            claimable: stream.claimable(env.block.time),
+            stream,
+        }
+    }
+
+    pub fn get_stream(deps: Deps, env: Env, id: u64) -> StdResult<StreamResp> {
+        let stream = STREAMS.load(deps.storage, id)?;
+        Ok(stream_resp(&env, stream))
+    }
+
+    pub fn list_streams(
+        deps: Deps,
+        env: Env,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    ) -> StdResult<ListStreamsResp> {
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+        let start = start_after.map(Bound::exclusive);
+
+        let streams = STREAMS
+            .range(deps.storage, start, None, Order::Ascending)
+            .take(limit)
+            .map(|item| item.map(|(_, stream)| stream_resp(&env, stream)))
+            .collect::<StdResult<_>>()?;
+
+        Ok(ListStreamsResp { streams })
+    }
+
+    pub fn get_reserved(deps: Deps) -> StdResult<ReservedResp> {
+        let reserved = STREAM_RESERVED
+            .range(deps.storage, None, None, Order::Ascending)
+            .map(|item| item.map(|(denom, amount)| Coin { denom, amount }))
+            .collect::<StdResult<_>>()?;
+
+        Ok(ReservedResp { reserved })
+    }
+
+    /// Address book lookup for the recipients of `txs`
+    fn recipients(deps: Deps, txs: &[Transaction]) -> StdResult<Vec<RecipientInfo>> {
+        txs.iter()
+            .filter_map(|tx| tx.tx_msg.recipient().map(|addr| (tx.id, addr)))
+            .map(|(tx_id, addr)| {
+                Ok(RecipientInfo {
+                    tx_id,
+                    addr: addr.clone(),
+                    contact: CONTACTS.may_load(deps.storage, addr)?,
+                })
+            })
+            .collect()
+    }
+
+    pub fn get_contact(deps: Deps, addr: Addr) -> StdResult<Contact> {
+        CONTACTS.load(deps.storage, &addr)
+    }
+
+    pub fn list_contacts(
+        deps: Deps,
+        start_after: Option<Addr>,
+        limit: Option<u32>,
+    ) -> StdResult<ListContactsResp> {
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+        let start = start_after.as_ref().map(Bound::exclusive);
+
+        let contacts = CONTACTS
+            .range(deps.storage, start, None, Order::Ascending)
+            .take(limit)
+            .map(|item| item.map(|(_, contact)| contact))
+            .collect::<StdResult<_>>()?;
+
+        Ok(ListContactsResp { contacts })
+    }
+
+    pub fn list_modules(
+        deps: Deps,
+        start_after: Option<Addr>,
+        limit: Option<u32>,
+    ) -> StdResult<ListModulesResp> {
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+        let start = start_after.as_ref().map(Bound::exclusive);
+
+        let modules = MODULES
+            .keys(deps.storage, start, None, Order::Ascending)
+            .take(limit)
+            .collect::<StdResult<_>>()?;
+
+        Ok(ListModulesResp { modules })
+    }
+
+    pub fn list_allowlist(
+        deps: Deps,
+        start_after: Option<Addr>,
+        limit: Option<u32>,
+    ) -> StdResult<ListAllowlistResp> {
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+        let start = start_after.as_ref().map(Bound::exclusive);
+
+        let addrs = ALLOWLIST
+            .keys(deps.storage, start, None, Order::Ascending)
+            .take(limit)
+            .collect::<StdResult<_>>()?;
+
+        Ok(ListAllowlistResp {
+            enabled: ALLOWLIST_MODE.load(deps.storage)?,
+            addrs,
+        })
+    }
+
+    pub fn get_roles(deps: Deps, addr: Addr) -> StdResult<RolesResp> {
+        let roles = if member_weight(deps, &addr)?.is_some() {
+            vec![Role::Proposer, Role::Signer, Role::Executor]
+        } else {
+            ROLES.may_load(deps.storage, &addr)?.unwrap_or_default()
+        };
+
+        Ok(RolesResp { roles })
+    }
+
+    pub fn list_roles(
+        deps: Deps,
+        start_after: Option<Addr>,
+        limit: Option<u32>,
+    ) -> StdResult<ListRolesResp> {
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+        let start = start_after.as_ref().map(Bound::exclusive);
+
+        let members = ROLES
+            .range(deps.storage, start, None, Order::Ascending)
+            .take(limit)
+            .map(|item| item.map(|(addr, roles)| RoleMember { addr, roles }))
+            .collect::<StdResult<_>>()?;
+
+        Ok(ListRolesResp { members })
+    }
+
+    pub fn threshold(deps: Deps) -> StdResult<ThresholdResponse> {
+        Ok(ThresholdResponse::AbsoluteCount {
+            weight: QUORUM.load(deps.storage)? as u64,
+            total_weight: total_weight(deps)?,
+        })
+    }
+
+    fn proposal_resp(deps: Deps, env: &Env, tx: &Transaction) -> StdResult<ProposalResp> {
+        let proposal = PROPOSALS.load(deps.storage, tx.id)?;
+
+        Ok(ProposalResp {
+            id: tx.id as u64,
+            title: proposal.title,
+            description: proposal.description,
+            msgs: cosmos_msgs(env, &tx.tx_msg),
+            status: proposal_status(deps, &env.block, tx)?,
+            expires: proposal.expires,
+            threshold: ThresholdResponse::AbsoluteCount {
+                weight: required_signatures(deps.storage, &tx.tx_msg)? as u64,
+                total_weight: total_weight(deps)?,
+            },
+            proposer: proposal.proposer,
+        })
+    }
+
+    /// Pending and completed transactions sorted by id, without the ones a recovery closed
+    /// along with their proposal
+    fn all_txs(deps: Deps) -> StdResult<Vec<Transaction>> {
+        let mut txs = PENDING_TXS.load(deps.storage)?.0;
+        txs.extend(COMPLETED_TXS.load(deps.storage)?.0);
+        txs.retain(|tx| PROPOSALS.has(deps.storage, tx.id));
+        txs.sort_by_key(|tx| tx.id);
+        Ok(txs)
+    }
+
+    pub fn proposal(deps: Deps, env: Env, proposal_id: u64) -> StdResult<ProposalResp> {
+        let tx = all_txs(deps)?
+            .into_iter()
+            .find(|tx| tx.id == proposal_id as u128)
+            .ok_or_else(|| StdError::not_found(format!("Transaction {}", proposal_id)))?;
+
+        proposal_resp(deps, &env, &tx)
+    }
+
+    pub fn list_proposals(
+        deps: Deps,
+        env: Env,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    ) -> StdResult<ListProposalsResp> {
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+
+        let proposals = all_txs(deps)?
+            .iter()
+            .filter(|tx| start_after.is_none_or(|start| tx.id > start as u128))
+            .take(limit)
+            .map(|tx| proposal_resp(deps, &env, tx))
+            .collect::<StdResult<_>>()?;
+
+        Ok(ListProposalsResp { proposals })
+    }
+
+    pub fn reverse_proposals(
+        deps: Deps,
+        env: Env,
+        start_before: Option<u64>,
+        limit: Option<u32>,
+    ) -> StdResult<ListProposalsResp> {
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+
+        let proposals = all_txs(deps)?
+            .iter()
+            .rev()
+            .filter(|tx| start_before.is_none_or(|start| tx.id < start as u128))
+            .take(limit)
+            .map(|tx| proposal_resp(deps, &env, tx))
+            .collect::<StdResult<_>>()?;
+
+        Ok(ListProposalsResp { proposals })
+    }
+
+    pub fn vote(deps: Deps, proposal_id: u64, voter: String) -> StdResult<VoteResp> {
+        let voter = Addr::unchecked(voter);
+        let vote = BALLOTS
+            .may_load(deps.storage, (proposal_id.into(), &voter))?
+            .map(|ballot| VoteInfo {
+                proposal_id,
+                voter: voter.to_string(),
+                vote: ballot.vote,
+                weight: ballot.weight,
+            });
+
+        Ok(VoteResp { vote })
+    }
+
+    pub fn list_votes(
+        deps: Deps,
+        proposal_id: u64,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> StdResult<ListVotesResp> {
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+        let start = start_after.map(|voter| Bound::ExclusiveRaw(voter.into()));
+
+        let votes = BALLOTS
+            .prefix(proposal_id.into())
+            .range(deps.storage, start, None, Order::Ascending)
+            .take(limit)
+            .map(|item| {
+                item.map(|(voter, ballot)| VoteInfo {
+                    proposal_id,
+                    voter: voter.to_string(),
+                    vote: ballot.vote,
+                    weight: ballot.weight,
+                })
+            })
+            .collect::<StdResult<_>>()?;
+
+        Ok(ListVotesResp { votes })
+    }
+
+    pub fn voter(deps: Deps, address: String) -> StdResult<VoterResp> {
+        let weight = member_weight(deps, &Addr::unchecked(address))?;
+
+        Ok(VoterResp { weight })
+    }
+
+    pub fn list_voters(
+        deps: Deps,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> StdResult<ListVotersResp> {
+        let voters = list_members(deps, start_after, limit)?
+            .members
+            .into_iter()
+            .map(|member| VoterDetail {
+                addr: member.addr,
+                weight: member.weight,
+            })
+            .collect();
+
+        Ok(ListVotersResp { voters })
+    }
+
+    pub fn member(deps: Deps, addr: String) -> StdResult<MemberResp> {
+        let weight = member_weight(deps, &Addr::unchecked(addr))?;
+
+        Ok(MemberResp { weight })
+    }
+
+    /// Owners sorted by address, or the members of the cw4 group if the wallet has one
+    pub fn list_members(
+        deps: Deps,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> StdResult<ListMembersResp> {
+        if let Some(group) = GROUP.may_load(deps.storage)? {
+            return deps
+                .querier
+                .query_wasm_smart(group, &GroupQueryMsg::ListMembers { start_after, limit });
+        }
+
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+        let mut admins = ADMINS.load(deps.storage)?;
+        admins.sort();
+
+        let members = admins
+            .into_iter()
+            .filter(|admin| {
+                start_after
+                    .as_ref()
+                    .is_none_or(|start| admin.as_str() > start.as_str())
+            })
+            .take(limit)
+            .map(|admin| Member {
+                addr: admin.to_string(),
+                weight: 1,
+            })
+            .collect();
+
+        Ok(ListMembersResp { members })
+    }
+
+    pub fn hooks(deps: Deps) -> StdResult<HooksResp> {
+        let hooks = HOOKS
+            .keys(deps.storage, None, None, Order::Ascending)
+            .map(|hook| hook.map(|hook| hook.to_string()))
+            .collect::<StdResult<_>>()?;
+
+        Ok(HooksResp { hooks })
     }
 }
@@ -1,7 +1,9 @@
-use cosmwasm_std::StdError;
+use cosmwasm_std::{StdError, Timestamp, Uint128};
+
+use crate::state::{Role, TxCategory};
 use thiserror::Error;
 
-#[derive(Error, Debug)]
+#[derive(Error, Debug, PartialEq)]
 pub enum ContractError {
     #[error("{0}")]
     Std(#[from] StdError),
@@ -9,18 +11,159 @@ pub enum ContractError {
     #[error("Unauthorized")]
     Unauthorized {},
 
+    #[error("An unknown reply ID was received.")]
+    UnknownReplyID {},
+
     #[error("Quorum: {quorum} is more than the number of owners: {owners}")]
     WrongQuorum { quorum: u32, owners: u32 },
 
     #[error("Number of owners can't be 0")]
     ZeroOwners,
 
+    #[error("Owners are managed by the cw4 group: {0}")]
+    GroupManaged(String),
+
+    #[error("Weight: {0} of the cw4 group is larger than the wallet can count")]
+    WeightTooLarge(u64),
+
     #[error("Transaction with tx_id: {0}, doesn't exist")]
     NonExistentTx(u128),
 
     #[error("You already signed transaction with id: {0}")]
     AlreadySigned(u128),
 
+    #[error("You already voted on transaction with id: {0}")]
+    AlreadyVoted(u128),
+
+    #[error("Transaction with id: {0} expired")]
+    Expired(u128),
+
+    #[error("Proposal expiration must be in the future")]
+    WrongExpiration,
+
+    #[error("Transaction with id: {0} can only be closed once it expired or was rejected")]
+    NotClosable(u128),
+
     #[error("Not enough admins signed this transaction, the quorum is {quorum} and only {num_signed} signed the transaction")]
     NotEnoughSignatures { quorum: u32, num_signed: u32 },
+
+    #[error("Number of guardians can't be 0")]
+    ZeroGuardians,
+
+    #[error("Guardian threshold: {threshold} must be between 1 and the number of guardians: {guardians}")]
+    WrongGuardianThreshold { threshold: u32, guardians: u32 },
+
+    #[error("No guardians are configured for this wallet")]
+    NoGuardians,
+
+    #[error("A recovery is already in progress")]
+    RecoveryInProgress,
+
+    #[error("There is no recovery in progress")]
+    NoRecovery,
+
+    #[error("You already approved the recovery")]
+    AlreadyApprovedRecovery,
+
+    #[error("Not enough guardians approved the recovery, the threshold is {threshold} and only {approvals} approved")]
+    NotEnoughGuardianApprovals { threshold: u32, approvals: u32 },
+
+    #[error("Recovery delay has not passed yet, the recovery can be executed at {ready_at}")]
+    RecoveryDelayNotPassed { ready_at: Timestamp },
+
+    #[error("The wallet is paused until {until}")]
+    Paused { until: Timestamp },
+
+    #[error("The wallet is already paused until {until}")]
+    AlreadyPaused { until: Timestamp },
+
+    #[error("The wallet can't be paused again until {until}")]
+    PauseCooldown { until: Timestamp },
+
+    #[error("Only bank transfers can be scheduled")]
+    UnschedulableMsg,
+
+    #[error("Not enough unreserved funds or rate limit left for a scheduled payment")]
+    ScheduleLimited,
+
+    #[error("Schedules need an interval, a count and coins greater than 0")]
+    InvalidSchedule,
+
+    #[error("Schedule with id: {0}, doesn't exist")]
+    NonExistentSchedule(u64),
+
+    #[error("Stream amount must be greater than 0 and its end must be after its start")]
+    InvalidStream,
+
+    #[error("Stream with id: {0}, doesn't exist")]
+    NonExistentStream(u64),
+
+    #[error("Threshold: {threshold} of the {category} category must be between 1 and the number of owners: {owners}")]
+    WrongThreshold {
+        category: TxCategory,
+        threshold: u32,
+        owners: u32,
+    },
+
+    #[error("Amount tiers of {denom} must have ascending bounds, end with an unbounded tier and require between 1 and the number of owners: {owners} signatures")]
+    InvalidAmountTiers { denom: String, owners: u32 },
+
+    #[error("Rate limit window must be greater than zero")]
+    InvalidRateLimit,
+
+    #[error("Rate limit of {denom} exceeded, available: {available}, required: {required}")]
+    RateLimitExceeded {
+        denom: String,
+        available: Uint128,
+        required: Uint128,
+    },
+
+    #[error("Recipient: {0} isn't on the allowlist")]
+    RecipientNotAllowed(String),
+
+    #[error("Guard rejected transaction: {reason}")]
+    GuardRejected { reason: String },
+
+    #[error("Module: {0} isn't enabled")]
+    NonExistentModule(String),
+
+    #[error("Hook: {0} isn't registered")]
+    NonExistentHook(String),
+
+    #[error("Modules and cw3 proposals can't send messages to the wallet itself")]
+    SelfCall,
+
+    #[error("Modules can only send bank transfers and execute contracts")]
+    UnsupportedModuleMsg,
+
+    #[error("Grants need a spend limit while a rate limit is set")]
+    UnlimitedGrant,
+
+    #[error("Generic grants of {0} aren't allowed while the allowlist mode or a rate limit is on")]
+    UnrestrictedGrant(String),
+
+    #[error("Messages whose funds can't be checked aren't allowed while the allowlist mode or a rate limit is on")]
+    UnverifiableMsg,
+
+    #[error("The signer role comes with ownership, use AddOwner and RemoveOwner instead")]
+    SignerRoleIsOwnership,
+
+    #[error("Address: {addr} doesn't have the {role} role")]
+    RoleNotGranted { addr: String, role: Role },
+
+    #[error("Contact label can't be empty")]
+    EmptyContactLabel,
+
+    #[error("Address: {0} is not in the address book")]
+    NonExistentContact(String),
+
+    #[error("Nothing to claim from stream with id: {0}")]
+    NothingToClaim(u64),
+
+    #[error("Insufficient unreserved funds, {available}{denom} available and {required}{denom} required")]
+    InsufficientFunds {
+        denom: String,
+        available: Uint128,
+        required: Uint128,
+    },
 }
@@ -0,0 +1,166 @@
+//! Events emitted by the multisig wallet and the factory.
+//!
+//! The chain prefixes custom event types with `wasm-`, so `multisig.tx` is indexed as
+//! `wasm-multisig.tx`. Every event has an `action` attribute, the remaining attributes
+//! depend on the event type:
+//!
+//! | Event | Actions | Attributes |
+//! |-------|---------|------------|
+//! | `multisig.tx` | `propose`, `sign`, `vote`, `execute`, `executed`, `execution_failed`, `auto_execute_failed`, `close` | `tx_id`, `actor`, `status`, `confirmations`, `msg_type`, `description`, `to` and `amount` for bank and IBC transfers, `channel_id` and `timeout` for IBC transfers, `proposal_id` and `vote` for chain governance votes, `grantee` for authz and fee grants with `authorization`, `msg_type_url`, `spend_limit` and `expiration` as they apply, `msg_count` and `amount` for cw3 proposals, `ballot` for cw3 votes other than yes, `error` on failures, `recipient_label` or `unknown_recipient` when proposing |
+//! | `multisig.owner` | `add`, `remove` | `owner`, `tx_id` unless added at instantiation or recovery or changed in the cw4 group, `invalidated` transactions whose vote was dropped when the group removes a member |
+//! | `multisig.config` | `instantiate`, `add_hook_failed`, `update_quorum`, `update_guardians`, `set_guard`, `set_allowlist_mode`, `set_threshold`, `set_amount_tiers`, `set_rate_limit`, `set_permissionless_execution`, `set_auto_execute` | `tx_id` unless instantiating, the new values, `group` when instantiating, `group` and `error` when the group's hook couldn't be added, `category` when setting a threshold, `denom` and `tiers` as `up_to:threshold` when setting amount tiers, `denom` and `effective_at` when setting a rate limit |
+//! | `multisig.pause` | `pause`, `unpause` | `actor` and `expires_at` when pausing, `tx_id` when unpausing |
+//! | `multisig.recovery` | `propose`, `approve`, `execute`, `veto` | `actor`, `owners` and `quorum` when proposing and executing, a recovery also revokes every granted role, resets the thresholds and amount tiers and closes every pending transaction with a `multisig.tx` `close` event |
+//! | `multisig.schedule` | `create`, `run`, `skip`, `cancel` | `schedule_id`, `tx_id` unless running or skipping, `to` and `amount` when running, `payments`, `remaining`, `reason` when skipping |
+//! | `multisig.role` | `grant`, `revoke` | `addr`, `role`, `tx_id` |
+//! | `multisig.allowlist` | `allow`, `disallow` | `addrs`, `tx_id` unless allowed at instantiation |
+//! | `multisig.module` | `enable`, `disable`, `execute` | `module`, `tx_id` unless executing, `msg_count` and `amount` moved when executing, a recovery also disables every module |
+//! | `multisig.hook` | `add`, `remove`, `notify_failed` | `hook` and `tx_id` when adding or removing, `error` when a hook failed to handle an owner change |
+//! | `multisig.contact` | `set`, `remove` | `addr`, `tx_id`, `label` and `tags` when setting |
+//! | `multisig.stream` | `create`, `claim`, `cancel` | `stream_id`, `tx_id` unless claiming, `recipient`, `amount`, `claimed`, `returned` when cancelling |
+//! | `multisig.factory` | `instantiate`, `create_wallet`, `wallet_created`, `update_code_id`, `update_nft_address` | `actor`, `code_id`, `label`, `wallet`, `owner`, `nft_address` |
+//!
+//! A transaction's `multisig.tx` events together with the events of its `tx_id` are enough
+//! to rebuild the wallet's history. Amounts use the SDK coin format, e.g. `10usei,5uatom`.
+//! The chain rejects empty attribute values, so removed settings and empty lists are
+//! reported as `none`.
+
+use cosmwasm_std::{Addr, Coin, Event, Timestamp};
+
+use crate::state::{vote_option_str, weighted_votes_str, Contact, Transaction, TxMsg};
+
+pub const TX_EVENT: &str = "multisig.tx";
+pub const OWNER_EVENT: &str = "multisig.owner";
+pub const CONFIG_EVENT: &str = "multisig.config";
+pub const PAUSE_EVENT: &str = "multisig.pause";
+pub const RECOVERY_EVENT: &str = "multisig.recovery";
+pub const SCHEDULE_EVENT: &str = "multisig.schedule";
+pub const STREAM_EVENT: &str = "multisig.stream";
+pub const CONTACT_EVENT: &str = "multisig.contact";
+pub const ROLE_EVENT: &str = "multisig.role";
+pub const ALLOWLIST_EVENT: &str = "multisig.allowlist";
+pub const MODULE_EVENT: &str = "multisig.module";
+pub const HOOK_EVENT: &str = "multisig.hook";
+pub const FACTORY_EVENT: &str = "multisig.factory";
+
+pub fn event(ty: &str, action: &str) -> Event {
+    Event::new(ty).add_attribute("action", action)
+}
+
+pub fn tx_event(action: &str, tx: &Transaction, actor: &Addr) -> Event {
+    base_tx_event(action, tx, actor, tx.tx_msg.to_string())
+}
+
+/// Event of a new proposal, flagging recipients that aren't in the address book
+pub fn propose_event(tx: &Transaction, actor: &Addr, contact: Option<&Contact>) -> Event {
+    let description = tx.tx_msg.labeled(contact).to_string();
+    let event = base_tx_event("propose", tx, actor, description);
+
+    match (tx.tx_msg.recipient(), contact) {
+        (None, _) => event,
+        (Some(_), Some(contact)) => event.add_attribute("recipient_label", &contact.label),
+        (Some(_), None) => event.add_attribute("unknown_recipient", "true"),
+    }
+}
+
+fn base_tx_event(action: &str, tx: &Transaction, actor: &Addr, description: String) -> Event {
+    let event = event(TX_EVENT, action)
+        .add_attribute("tx_id", tx.id.to_string())
+        .add_attribute("actor", actor)
+        .add_attribute("status", tx.status.to_string())
+        .add_attribute("confirmations", tx.num_confirmations.to_string())
+        .add_attribute("msg_type", tx.tx_msg.msg_type())
+        .add_attribute("description", description);
+
+    match &tx.tx_msg {
+        TxMsg::TxBank { to, coins } => event
+            .add_attribute("to", to)
+            .add_attribute("amount", coins_to_string(coins)),
+        TxMsg::IbcTransfer {
+            channel_id,
+            to_address,
+            amount,
+            timeout,
+        } => event
+            .add_attribute("channel_id", channel_id)
+            .add_attribute("to", to_address)
+            .add_attribute("amount", amount.to_string())
+            .add_attribute("timeout", timeout.to_string()),
+        TxMsg::GovVote { proposal_id, vote } => event
+            .add_attribute("proposal_id", proposal_id.to_string())
+            .add_attribute("vote", vote_option_str(vote)),
+        TxMsg::GovVoteWeighted {
+            proposal_id,
+            options,
+        } => event
+            .add_attribute("proposal_id", proposal_id.to_string())
+            .add_attribute("vote", weighted_votes_str(options)),
+        TxMsg::AuthzGrant {
+            grantee,
+            authorization,
+            expiration,
+        } => event
+            .add_attribute("grantee", grantee)
+            .add_attribute("authorization", authorization.to_string())
+            .add_attribute("expiration", expiration_to_string(expiration)),
+        TxMsg::AuthzRevoke {
+            grantee,
+            msg_type_url,
+        } => event
+            .add_attribute("grantee", grantee)
+            .add_attribute("msg_type_url", msg_type_url),
+        TxMsg::GrantAllowance {
+            grantee,
+            spend_limit,
+            expiration,
+        } => event
+            .add_attribute("grantee", grantee)
+            .add_attribute("spend_limit", coins_to_string(spend_limit))
+            .add_attribute("expiration", expiration_to_string(expiration)),
+        TxMsg::RevokeAllowance { grantee } => event.add_attribute("grantee", grantee),
+        TxMsg::Messages { msgs } => event
+            .add_attribute("msg_count", msgs.len().to_string())
+            .add_attribute("amount", coins_to_string(&tx.tx_msg.outflow())),
+        TxMsg::TxSelf(_) => event,
+    }
+}
+
+pub fn owner_event(action: &str, owner: &Addr) -> Event {
+    event(OWNER_EVENT, action).add_attribute("owner", owner)
+}
+
+pub fn config_event(action: &str, tx_id: u128) -> Event {
+    event(CONFIG_EVENT, action).add_attribute("tx_id", tx_id.to_string())
+}
+
+pub fn coins_to_string(coins: &[Coin]) -> String {
+    list_to_string(coins.iter().map(Coin::to_string))
+}
+
+fn expiration_to_string(expiration: &Option<Timestamp>) -> String {
+    match expiration {
+        Some(time) => time.to_string(),
+        None => NONE.to_string(),
+    }
+}
+
+pub fn addrs_to_string(addrs: &[Addr]) -> String {
+    list_to_string(addrs.iter().map(Addr::to_string))
+}
+
+/// Comma separated `items`, `none` when there are none
+pub fn list_to_string(items: impl IntoIterator<Item = impl ToString>) -> String {
+    let list = items
+        .into_iter()
+        .map(|item| item.to_string())
+        .collect::<Vec<_>>()
+        .join(",");
+    if list.is_empty() {
+        NONE.to_string()
+    } else {
+        list
+    }
+}
+
+/// Attribute value of a removed setting
+pub const NONE: &str = "none";
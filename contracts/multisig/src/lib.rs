@@ -1,6 +1,8 @@
 pub mod contract;
 mod error;
+pub mod events;
 pub mod msg;
+pub mod proto;
 pub mod state;
 
 pub use crate::error::ContractError;
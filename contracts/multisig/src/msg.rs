@@ -1,21 +1,113 @@
 use cosmwasm_schema::{cw_serde, QueryResponses};
-use cosmwasm_std::Addr;
+use cosmwasm_std::{Addr, Coin, CosmosMsg, Timestamp, Uint128};
+use cw_utils::{Expiration, ThresholdResponse};
 
-use crate::state::{CompletedTransactions, PendingTransactions, TxMsg};
+use crate::state::{
+    AmountTier, CompletedTransactions, Contact, GuardianSet, PendingRateLimit, PendingTransactions,
+    RateLimit, Recovery, Role, Schedule, Stream, Transaction, TxCategory, TxMsg, Vote,
+};
 
 #[cw_serde]
 pub struct InstantiateMsg {
-    /// Owners that need to sign transactions
+    /// Owners that need to sign transactions, empty when `group` is set
     pub owners: Vec<Addr>,
-    /// Quorum must be < owners.len()
+    /// Quorum must be < owners.len(), or the group's total weight
     pub quorum: u32,
+    /// cw4 group the owners and their weights are read from instead of `owners`. The wallet
+    /// registers a member-changed hook on it, which only succeeds if the wallet is the
+    /// group's admin, otherwise the admin has to add the hook.
+    pub group: Option<Addr>,
+    /// Optional guardians that can recover the wallet if the owners lose their keys
+    pub guardians: Option<GuardianSet>,
+    /// Seconds a pause lasts unless lifted earlier, defaults to 3 days
+    pub pause_duration: Option<u64>,
+    /// Allow anyone to execute a transaction that reached quorum, defaults to false
+    pub permissionless_execution: Option<bool>,
+    /// Execute transactions as soon as they reach quorum, defaults to false
+    pub auto_execute: Option<bool>,
+    /// Seconds before a loosened rate limit takes effect, defaults to 1 day
+    pub rate_limit_delay: Option<u64>,
+    /// Enables the allowlist mode with these recipients
+    pub allowlist: Option<Vec<Addr>>,
 }
 
 #[cw_serde]
 pub enum ExecuteMsg {
-    CreateTransaction { tx_msg: TxMsg },
-    SignTransactions { tx_id: u128 },
-    ExecuteTransaction { tx_id: u128 },
+    /// `auto_execute` overrides the wallet's setting for this transaction
+    CreateTransaction {
+        tx_msg: TxMsg,
+        auto_execute: Option<bool>,
+    },
+    SignTransactions {
+        tx_id: u128,
+    },
+    ExecuteTransaction {
+        tx_id: u128,
+    },
+    /// Callable only by a guardian. Starts a recovery replacing the owners and quorum,
+    /// the proposer's approval is counted.
+    ProposeRecovery {
+        owners: Vec<Addr>,
+        quorum: u32,
+    },
+    /// Callable only by a guardian. Approves the recovery in progress.
+    ApproveRecovery {},
+    /// Callable only by a guardian. Replaces the owners and quorum once enough guardians
+    /// approved and the recovery delay has passed. Pending transactions are dropped.
+    ExecuteRecovery {},
+    /// Callable only by an owner. Cancels the recovery in progress.
+    VetoRecovery {},
+    /// Callable only by an owner. Blocks creating and executing transactions until
+    /// the pause expires or a `SelfTx::Unpause` is executed. The wallet can't be paused
+    /// again for as long as the pause lasts once it's over.
+    Pause {},
+    /// Callable by anyone. Makes the payments of every schedule that are due, as many as
+    /// the unreserved funds and rate limits allow. Schedules that can't be paid are skipped.
+    RunDueSchedules {},
+    /// Callable only by the stream's recipient. Withdraws the vested funds, or at most
+    /// `amount` of them so a claim can fit in the rate limit.
+    ClaimStream {
+        id: u64,
+        amount: Option<Uint128>,
+    },
+    /// Callable only by enabled modules. The wallet sends `msgs` without a vote, funds
+    /// they move still go through the pause, allowlist, reserves and rate limits.
+    /// Only bank transfers and contract executions are accepted.
+    ExecuteFromModule {
+        msgs: Vec<CosmosMsg>,
+    },
+    /// Callable only by the cw4 group. Drops the votes of removed members from pending
+    /// transactions.
+    MemberChangedHook {
+        diffs: Vec<MemberDiff>,
+    },
+    /// Callable only by the wallet itself. Sends the messages of an executed cw3 proposal.
+    SendMessages {
+        msgs: Vec<CosmosMsg>,
+    },
+    /// cw3 proposal, creates a `TxMsg::Messages` transaction. Votes are accepted until
+    /// `latest`, the wallet doesn't limit the voting period. While the allowlist mode or
+    /// a rate limit is on, only bank, contract, IBC transfer and gov messages can be sent.
+    Propose {
+        title: String,
+        description: String,
+        msgs: Vec<CosmosMsg>,
+        latest: Option<Expiration>,
+    },
+    /// cw3 vote, voting yes is the same as `SignTransactions`
+    Vote {
+        proposal_id: u64,
+        vote: Vote,
+    },
+    /// cw3 alias of `ExecuteTransaction`
+    Execute {
+        proposal_id: u64,
+    },
+    /// Callable by anyone. Rejects a transaction that expired or can no longer get
+    /// enough signatures.
+    Close {
+        proposal_id: u64,
+    },
 }
 
 #[cw_serde]
@@ -24,8 +116,11 @@ pub enum QueryMsg {
     #[returns(ListPendingResp)]
     ListPending {},
 
-    #[returns(ListPendingResp)]
-    ListCompletedTxs {offset: Option<u32>, limit: Option<u32>},
+    #[returns(ListCompletedTxsResp)]
+    ListCompletedTxs {
+        offset: Option<u32>,
+        limit: Option<u32>,
+    },
 
     #[returns(ListAdminsResp)]
     ListAdmins {},
@@ -33,8 +128,146 @@ pub enum QueryMsg {
     #[returns(ListSignedResp)]
     ListSigned { admin: Addr, tx_id: u128 },
 
+    /// The quorum, or the signatures `tx_id` requires given its category and amounts
     #[returns(u32)]
-    GetQuorum {},
+    GetQuorum { tx_id: Option<u128> },
+
+    #[returns(AmountTiersResp)]
+    GetAmountTiers { denom: String },
+
+    /// Rate limit of `denom` and its usage in the current window
+    #[returns(RateLimitResp)]
+    GetRateLimit { denom: String },
+
+    #[returns(ListAllowlistResp)]
+    ListAllowlist {
+        start_after: Option<Addr>,
+        limit: Option<u32>,
+    },
+
+    #[returns(ListModulesResp)]
+    ListModules {
+        start_after: Option<Addr>,
+        limit: Option<u32>,
+    },
+
+    /// Signatures required per transaction category
+    #[returns(ThresholdsResp)]
+    GetThresholds {},
+
+    #[returns(GuardiansResp)]
+    GetGuardians {},
+
+    #[returns(RecoveryResp)]
+    GetRecovery {},
+
+    #[returns(PauseResp)]
+    GetPauseState {},
+
+    #[returns(ConfigResp)]
+    GetConfig {},
+
+    #[returns(Schedule)]
+    GetSchedule { id: u64 },
+
+    #[returns(ListSchedulesResp)]
+    ListSchedules {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+
+    #[returns(StreamResp)]
+    GetStream { id: u64 },
+
+    #[returns(ListStreamsResp)]
+    ListStreams {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+
+    /// Funds reserved for streams
+    #[returns(ReservedResp)]
+    GetReserved {},
+
+    #[returns(Contact)]
+    GetContact { addr: Addr },
+
+    #[returns(ListContactsResp)]
+    ListContacts {
+        start_after: Option<Addr>,
+        limit: Option<u32>,
+    },
+
+    /// Roles held by `addr`, owners hold every role
+    #[returns(RolesResp)]
+    GetRoles { addr: Addr },
+
+    /// Roles granted to addresses that aren't owners
+    #[returns(ListRolesResp)]
+    ListRoles {
+        start_after: Option<Addr>,
+        limit: Option<u32>,
+    },
+
+    /// cw3 threshold, the quorum out of the owners
+    #[returns(ThresholdResponse)]
+    Threshold {},
+
+    /// Transaction as a cw3 proposal
+    #[returns(ProposalResp)]
+    Proposal { proposal_id: u64 },
+
+    #[returns(ListProposalsResp)]
+    ListProposals {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+
+    #[returns(ListProposalsResp)]
+    ReverseProposals {
+        start_before: Option<u64>,
+        limit: Option<u32>,
+    },
+
+    #[returns(VoteResp)]
+    Vote { proposal_id: u64, voter: String },
+
+    #[returns(ListVotesResp)]
+    ListVotes {
+        proposal_id: u64,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+
+    /// Owners are the cw3 voters
+    #[returns(VoterResp)]
+    Voter { address: String },
+
+    #[returns(ListVotersResp)]
+    ListVoters {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+
+    /// Owners as cw4 members, heights aren't tracked so `at_height` is ignored
+    #[returns(MemberResp)]
+    Member {
+        addr: String,
+        at_height: Option<u64>,
+    },
+
+    #[returns(ListMembersResp)]
+    ListMembers {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+
+    #[returns(TotalWeightResp)]
+    TotalWeight { at_height: Option<u64> },
+
+    /// Contracts notified when the owners change
+    #[returns(HooksResp)]
+    Hooks {},
 }
 
 #[cw_serde]
@@ -50,10 +283,285 @@ pub struct ListAdminsResp {
 #[cw_serde]
 pub struct ListPendingResp {
     pub transactions: PendingTransactions,
+    pub recipients: Vec<RecipientInfo>,
 }
 
 #[cw_serde]
 pub struct ListCompletedTxsResp {
     pub transactions: CompletedTransactions,
+    pub recipients: Vec<RecipientInfo>,
+}
+
+/// Recipient of a listed transaction, `contact` is `None` when it isn't in the address book
+#[cw_serde]
+pub struct RecipientInfo {
+    pub tx_id: u128,
+    pub addr: Addr,
+    pub contact: Option<Contact>,
+}
+
+#[cw_serde]
+pub struct GuardiansResp {
+    pub guardians: Option<GuardianSet>,
+}
+
+#[cw_serde]
+pub struct RecoveryResp {
+    pub recovery: Option<Recovery>,
+}
+
+#[cw_serde]
+pub struct PauseResp {
+    pub paused: bool,
+    pub paused_by: Option<Addr>,
+    pub expires_at: Option<Timestamp>,
+    /// Until when the wallet can't be paused again
+    pub cooldown_until: Option<Timestamp>,
+}
+
+#[cw_serde]
+pub struct ConfigResp {
+    pub quorum: u32,
+    pub pause_duration: u64,
+    pub permissionless_execution: bool,
+    pub auto_execute: bool,
+    pub rate_limit_delay: u64,
+    pub allowlist_mode: bool,
+    pub guard: Option<Addr>,
+    pub group: Option<Addr>,
+}
+
+#[cw_serde]
+pub struct ListSchedulesResp {
+    pub schedules: Vec<Schedule>,
+}
+
+#[cw_serde]
+pub struct StreamResp {
+    pub stream: Stream,
+    pub vested: Uint128,
+    pub claimable: Uint128,
+}
+
+#[cw_serde]
+pub struct ListStreamsResp {
+    pub streams: Vec<StreamResp>,
+}
+
+#[cw_serde]
+pub struct ReservedResp {
+    pub reserved: Vec<Coin>,
 }
 
+#[cw_serde]
+pub struct ListContactsResp {
+    pub contacts: Vec<Contact>,
+}
+
+#[cw_serde]
+pub struct RolesResp {
+    pub roles: Vec<Role>,
+}
+
+#[cw_serde]
+pub struct RoleMember {
+    pub addr: Addr,
+    pub roles: Vec<Role>,
+}
+
+#[cw_serde]
+pub struct ListRolesResp {
+    pub members: Vec<RoleMember>,
+}
+
+#[cw_serde]
+pub struct CategoryThreshold {
+    pub category: TxCategory,
+    pub threshold: u32,
+    /// Whether the threshold was set, otherwise it's the quorum
+    pub custom: bool,
+}
+
+#[cw_serde]
+pub struct ThresholdsResp {
+    pub thresholds: Vec<CategoryThreshold>,
+}
+
+#[cw_serde]
+pub struct AmountTiersResp {
+    pub tiers: Vec<AmountTier>,
+}
+
+#[cw_serde]
+pub struct RateLimitResp {
+    pub limit: Option<RateLimit>,
+    /// Loosened limit that isn't in effect yet
+    pub pending: Option<PendingRateLimit>,
+    pub used: Uint128,
+    /// `None` when there's no limit
+    pub available: Option<Uint128>,
+}
+
+#[cw_serde]
+pub struct ListAllowlistResp {
+    pub enabled: bool,
+    pub addrs: Vec<Addr>,
+}
+
+/// Query a guard contract must answer before a transaction is executed
+#[cw_serde]
+#[derive(QueryResponses)]
+pub enum GuardQueryMsg {
+    #[returns(GuardCheckResp)]
+    CheckTransaction {
+        tx: Transaction,
+        /// Owners that signed the transaction
+        signers: Vec<Addr>,
+        executor: Addr,
+    },
+}
+
+#[cw_serde]
+pub struct GuardCheckResp {
+    pub allowed: bool,
+    pub reason: Option<String>,
+}
+
+/// Message a guard contract receives once a transaction was executed
+#[cw_serde]
+pub enum GuardExecuteMsg {
+    AfterExecution { tx: Transaction, success: bool },
+}
+
+/// cw3 proposal status
+#[cw_serde]
+pub enum ProposalStatus {
+    Pending,
+    Open,
+    Rejected,
+    Passed,
+    Executed,
+}
+
+/// Transaction as a cw3 proposal, `msgs` is empty for changes to the wallet itself
+#[cw_serde]
+pub struct ProposalResp {
+    pub id: u64,
+    pub title: String,
+    pub description: String,
+    pub msgs: Vec<CosmosMsg>,
+    pub status: ProposalStatus,
+    pub expires: Expiration,
+    pub threshold: ThresholdResponse,
+    pub proposer: Addr,
+}
+
+#[cw_serde]
+pub struct ListProposalsResp {
+    pub proposals: Vec<ProposalResp>,
+}
+
+#[cw_serde]
+pub struct VoteInfo {
+    pub proposal_id: u64,
+    pub voter: String,
+    pub vote: Vote,
+    pub weight: u64,
+}
+
+#[cw_serde]
+pub struct VoteResp {
+    pub vote: Option<VoteInfo>,
+}
+
+#[cw_serde]
+pub struct ListVotesResp {
+    pub votes: Vec<VoteInfo>,
+}
+
+#[cw_serde]
+pub struct VoterResp {
+    pub weight: Option<u64>,
+}
+
+#[cw_serde]
+pub struct VoterDetail {
+    pub addr: String,
+    pub weight: u64,
+}
+
+#[cw_serde]
+pub struct ListVotersResp {
+    pub voters: Vec<VoterDetail>,
+}
+
+/// Queries the wallet sends to its cw4 group
+#[cw_serde]
+#[derive(QueryResponses)]
+pub enum GroupQueryMsg {
+    #[returns(MemberResp)]
+    Member {
+        addr: String,
+        at_height: Option<u64>,
+    },
+
+    #[returns(ListMembersResp)]
+    ListMembers {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+
+    #[returns(TotalWeightResp)]
+    TotalWeight { at_height: Option<u64> },
+}
+
+/// Message registering the wallet's hook on its cw4 group
+#[cw_serde]
+pub enum GroupExecuteMsg {
+    AddHook { addr: String },
+}
+
+/// cw4 message the wallet sends to its hooks when the owners change
+#[cw_serde]
+pub enum HookExecuteMsg {
+    MemberChangedHook { diffs: Vec<MemberDiff> },
+}
+
+#[cw_serde]
+pub struct HooksResp {
+    pub hooks: Vec<String>,
+}
+
+#[cw_serde]
+pub struct MemberResp {
+    pub weight: Option<u64>,
+}
+
+#[cw_serde]
+pub struct Member {
+    pub addr: String,
+    pub weight: u64,
+}
+
+#[cw_serde]
+pub struct ListMembersResp {
+    pub members: Vec<Member>,
+}
+
+#[cw_serde]
+pub struct TotalWeightResp {
+    pub weight: u64,
+}
+
+/// Change of a cw4 member, `None` when it isn't a member before or after the change
+#[cw_serde]
+pub struct MemberDiff {
+    pub key: String,
+    pub old: Option<u64>,
+    pub new: Option<u64>,
+}
+
+#[cw_serde]
+pub struct ListModulesResp {
+    pub modules: Vec<Addr>,
+}
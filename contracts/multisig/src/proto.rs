@@ -0,0 +1,370 @@
+//! Protobuf encoding of the Cosmos SDK messages the wallet sends as Stargate messages,
+//! for modules CosmWasm has no typed message for.
+
+use cosmwasm_std::{Addr, Binary, Coin, CosmosMsg, Timestamp};
+use prost::Message;
+
+use crate::state::Authorization;
+
+pub const MSG_GRANT: &str = "/cosmos.authz.v1beta1.MsgGrant";
+pub const MSG_REVOKE: &str = "/cosmos.authz.v1beta1.MsgRevoke";
+pub const GENERIC_AUTHORIZATION: &str = "/cosmos.authz.v1beta1.GenericAuthorization";
+pub const SEND_AUTHORIZATION: &str = "/cosmos.bank.v1beta1.SendAuthorization";
+pub const MSG_GRANT_ALLOWANCE: &str = "/cosmos.feegrant.v1beta1.MsgGrantAllowance";
+pub const MSG_REVOKE_ALLOWANCE: &str = "/cosmos.feegrant.v1beta1.MsgRevokeAllowance";
+pub const BASIC_ALLOWANCE: &str = "/cosmos.feegrant.v1beta1.BasicAllowance";
+
+#[derive(Clone, PartialEq, Message)]
+pub struct Any {
+    #[prost(string, tag = "1")]
+    pub type_url: String,
+    #[prost(bytes = "vec", tag = "2")]
+    pub value: Vec<u8>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct ProtoCoin {
+    #[prost(string, tag = "1")]
+    pub denom: String,
+    #[prost(string, tag = "2")]
+    pub amount: String,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct ProtoTimestamp {
+    #[prost(int64, tag = "1")]
+    pub seconds: i64,
+    #[prost(int32, tag = "2")]
+    pub nanos: i32,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct SendAuthorization {
+    #[prost(message, repeated, tag = "1")]
+    pub spend_limit: Vec<ProtoCoin>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct GenericAuthorization {
+    #[prost(string, tag = "1")]
+    pub msg: String,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct Grant {
+    #[prost(message, optional, tag = "1")]
+    pub authorization: Option<Any>,
+    #[prost(message, optional, tag = "2")]
+    pub expiration: Option<ProtoTimestamp>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct MsgGrant {
+    #[prost(string, tag = "1")]
+    pub granter: String,
+    #[prost(string, tag = "2")]
+    pub grantee: String,
+    #[prost(message, optional, tag = "3")]
+    pub grant: Option<Grant>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct MsgRevoke {
+    #[prost(string, tag = "1")]
+    pub granter: String,
+    #[prost(string, tag = "2")]
+    pub grantee: String,
+    #[prost(string, tag = "3")]
+    pub msg_type_url: String,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct BasicAllowance {
+    #[prost(message, repeated, tag = "1")]
+    pub spend_limit: Vec<ProtoCoin>,
+    #[prost(message, optional, tag = "2")]
+    pub expiration: Option<ProtoTimestamp>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct MsgGrantAllowance {
+    #[prost(string, tag = "1")]
+    pub granter: String,
+    #[prost(string, tag = "2")]
+    pub grantee: String,
+    #[prost(message, optional, tag = "3")]
+    pub allowance: Option<Any>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct MsgRevokeAllowance {
+    #[prost(string, tag = "1")]
+    pub granter: String,
+    #[prost(string, tag = "2")]
+    pub grantee: String,
+}
+
+fn any(type_url: &str, msg: impl Message) -> Any {
+    Any {
+        type_url: type_url.to_string(),
+        value: msg.encode_to_vec(),
+    }
+}
+
+fn stargate(type_url: &str, msg: impl Message) -> CosmosMsg {
+    CosmosMsg::Stargate {
+        type_url: type_url.to_string(),
+        value: Binary(msg.encode_to_vec()),
+    }
+}
+
+fn coins(coins: &[Coin]) -> Vec<ProtoCoin> {
+    coins
+        .iter()
+        .map(|coin| ProtoCoin {
+            denom: coin.denom.clone(),
+            amount: coin.amount.to_string(),
+        })
+        .collect()
+}
+
+fn timestamp(time: Timestamp) -> ProtoTimestamp {
+    ProtoTimestamp {
+        seconds: time.seconds() as i64,
+        nanos: time.subsec_nanos() as i32,
+    }
+}
+
+/// `MsgGrant` letting `grantee` act on behalf of `granter`
+pub fn grant(
+    granter: &Addr,
+    grantee: &Addr,
+    authorization: &Authorization,
+    expiration: Option<Timestamp>,
+) -> CosmosMsg {
+    let authorization = match authorization {
+        Authorization::Send { spend_limit } => any(
+            SEND_AUTHORIZATION,
+            SendAuthorization {
+                spend_limit: coins(spend_limit),
+            },
+        ),
+        Authorization::Generic { msg_type_url } => any(
+            GENERIC_AUTHORIZATION,
+            GenericAuthorization {
+                msg: msg_type_url.clone(),
+            },
+        ),
+    };
+
+    stargate(
+        MSG_GRANT,
+        MsgGrant {
+            granter: granter.to_string(),
+            grantee: grantee.to_string(),
+            grant: Some(Grant {
+                authorization: Some(authorization),
+                expiration: expiration.map(timestamp),
+            }),
+        },
+    )
+}
+
+pub fn revoke(granter: &Addr, grantee: &Addr, msg_type_url: &str) -> CosmosMsg {
+    stargate(
+        MSG_REVOKE,
+        MsgRevoke {
+            granter: granter.to_string(),
+            grantee: grantee.to_string(),
+            msg_type_url: msg_type_url.to_string(),
+        },
+    )
+}
+
+/// `MsgGrantAllowance` with a `BasicAllowance`, an empty `spend_limit` doesn't limit the fees
+pub fn grant_allowance(
+    granter: &Addr,
+    grantee: &Addr,
+    spend_limit: &[Coin],
+    expiration: Option<Timestamp>,
+) -> CosmosMsg {
+    let allowance = BasicAllowance {
+        spend_limit: coins(spend_limit),
+        expiration: expiration.map(timestamp),
+    };
+
+    stargate(
+        MSG_GRANT_ALLOWANCE,
+        MsgGrantAllowance {
+            granter: granter.to_string(),
+            grantee: grantee.to_string(),
+            allowance: Some(any(BASIC_ALLOWANCE, allowance)),
+        },
+    )
+}
+
+pub fn revoke_allowance(granter: &Addr, grantee: &Addr) -> CosmosMsg {
+    stargate(
+        MSG_REVOKE_ALLOWANCE,
+        MsgRevokeAllowance {
+            granter: granter.to_string(),
+            grantee: grantee.to_string(),
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use cosmwasm_std::coins as std_coins;
+
+    use super::*;
+
+    fn unwrap_stargate(msg: CosmosMsg) -> (String, Vec<u8>) {
+        match msg {
+            CosmosMsg::Stargate { type_url, value } => (type_url, value.0),
+            msg => panic!("expected a stargate message, got {:?}", msg),
+        }
+    }
+
+    #[test]
+    fn revoke_allowance_encoding() {
+        let msg = revoke_allowance(&Addr::unchecked("ab"), &Addr::unchecked("c"));
+        let (type_url, value) = unwrap_stargate(msg);
+
+        assert_eq!(MSG_REVOKE_ALLOWANCE, type_url);
+        // field 1 "ab", field 2 "c"
+        assert_eq!(vec![0x0a, 2, b'a', b'b', 0x12, 1, b'c'], value);
+    }
+
+    #[test]
+    fn revoke_encoding() {
+        let msg = revoke(&Addr::unchecked("a"), &Addr::unchecked("b"), "/m");
+        let (type_url, value) = unwrap_stargate(msg);
+
+        assert_eq!(MSG_REVOKE, type_url);
+        assert_eq!(
+            vec![0x0a, 1, b'a', 0x12, 1, b'b', 0x1a, 2, b'/', b'm'],
+            value
+        );
+    }
+
+    #[test]
+    fn send_authorization_encoding() {
+        let expiration = Timestamp::from_nanos(1_500_000_005);
+        let msg = grant(
+            &Addr::unchecked("a"),
+            &Addr::unchecked("b"),
+            &Authorization::Send {
+                spend_limit: std_coins(10, "usei"),
+            },
+            Some(expiration),
+        );
+        let (type_url, value) = unwrap_stargate(msg);
+        assert_eq!(MSG_GRANT, type_url);
+
+        // Coin { denom: "usei", amount: "10" }
+        let coin = [
+            vec![0x0a, 4],
+            b"usei".to_vec(),
+            vec![0x12, 2],
+            b"10".to_vec(),
+        ]
+        .concat();
+        // SendAuthorization { spend_limit: [coin] }
+        let authorization = [vec![0x0a, coin.len() as u8], coin].concat();
+        // Any { type_url, value: authorization }
+        let any = [
+            vec![0x0a, SEND_AUTHORIZATION.len() as u8],
+            SEND_AUTHORIZATION.as_bytes().to_vec(),
+            vec![0x12, authorization.len() as u8],
+            authorization,
+        ]
+        .concat();
+        // Timestamp { seconds: 1, nanos: 500000005 }
+        let timestamp = vec![0x08, 1, 0x10, 0x85, 0xca, 0xb5, 0xee, 0x01];
+        // Grant { authorization: any, expiration: timestamp }
+        let grant = [
+            vec![0x0a, any.len() as u8],
+            any,
+            vec![0x12, timestamp.len() as u8],
+            timestamp,
+        ]
+        .concat();
+        let expected = [
+            vec![0x0a, 1, b'a', 0x12, 1, b'b', 0x1a, grant.len() as u8],
+            grant,
+        ]
+        .concat();
+        assert_eq!(expected, value);
+
+        let decoded = MsgGrant::decode(value.as_slice()).unwrap();
+        let authorization = decoded.grant.unwrap().authorization.unwrap();
+        assert_eq!(
+            SendAuthorization {
+                spend_limit: vec![ProtoCoin {
+                    denom: "usei".to_string(),
+                    amount: "10".to_string(),
+                }],
+            },
+            SendAuthorization::decode(authorization.value.as_slice()).unwrap()
+        );
+    }
+
+    #[test]
+    fn generic_authorization_without_expiration() {
+        let msg = grant(
+            &Addr::unchecked("a"),
+            &Addr::unchecked("b"),
+            &Authorization::Generic {
+                msg_type_url: "/cosmos.staking.v1beta1.MsgDelegate".to_string(),
+            },
+            None,
+        );
+        let (_, value) = unwrap_stargate(msg);
+
+        let grant = MsgGrant::decode(value.as_slice()).unwrap().grant.unwrap();
+        assert_eq!(None, grant.expiration);
+        let authorization = grant.authorization.unwrap();
+        assert_eq!(GENERIC_AUTHORIZATION, authorization.type_url);
+        assert_eq!(
+            "/cosmos.staking.v1beta1.MsgDelegate",
+            GenericAuthorization::decode(authorization.value.as_slice())
+                .unwrap()
+                .msg
+        );
+    }
+
+    #[test]
+    fn basic_allowance_encoding() {
+        let msg = grant_allowance(
+            &Addr::unchecked("a"),
+            &Addr::unchecked("b"),
+            &std_coins(5, "usei"),
+            Some(Timestamp::from_seconds(100)),
+        );
+        let (type_url, value) = unwrap_stargate(msg);
+        assert_eq!(MSG_GRANT_ALLOWANCE, type_url);
+
+        let decoded = MsgGrantAllowance::decode(value.as_slice()).unwrap();
+        assert_eq!("a", decoded.granter);
+        assert_eq!("b", decoded.grantee);
+        let allowance = decoded.allowance.unwrap();
+        assert_eq!(BASIC_ALLOWANCE, allowance.type_url);
+        // spend_limit: [Coin { denom: "usei", amount: "5" }], expiration: { seconds: 100 }
+        let coin = [vec![0x0a, 4], b"usei".to_vec(), vec![0x12, 1, b'5']].concat();
+        let expected = [vec![0x0a, coin.len() as u8], coin, vec![0x12, 2, 0x08, 100]].concat();
+        assert_eq!(expected, allowance.value);
+    }
+
+    #[test]
+    fn unlimited_allowance_omits_spend_limit() {
+        let msg = grant_allowance(&Addr::unchecked("a"), &Addr::unchecked("b"), &[], None);
+        let (_, value) = unwrap_stargate(msg);
+
+        let allowance = MsgGrantAllowance::decode(value.as_slice())
+            .unwrap()
+            .allowance
+            .unwrap();
+        assert!(allowance.value.is_empty());
+    }
+}
@@ -1,27 +1,621 @@
 use std::fmt::Display;
 
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{Addr, Coin};
+use cosmwasm_std::{
+    from_json, Addr, BankMsg, Coin, CosmosMsg, Env, IbcMsg, IbcTimeout, IbcTimeoutBlock, Timestamp,
+    Uint128, VoteOption, WasmMsg, WeightedVoteOption,
+};
 use cw_storage_plus::{Item, Map};
+use cw_utils::Expiration;
+use serde::Deserialize;
 
 #[cw_serde]
 pub struct Transaction {
     pub tx_msg: TxMsg,
     pub id: u128,
     pub num_confirmations: u32,
+    pub status: TxStatus,
+    /// Address that executed the transaction
+    pub executor: Option<Addr>,
+    /// Overrides the wallet's auto-execute setting for this transaction
+    pub auto_execute: Option<bool>,
+}
+
+#[cw_serde]
+pub enum TxStatus {
+    Pending,
+    Executed,
+    /// The transaction's messages failed, it stays pending so it can be retried
+    ExecutionFailed {
+        error: String,
+    },
+    /// Closed without being executed once it expired or too many owners voted against it
+    Rejected,
 }
 
 #[cw_serde]
 pub enum TxMsg {
-    TxBank { to: Addr, coins: Vec<Coin> },
+    TxBank {
+        to: Addr,
+        coins: Vec<Coin>,
+    },
     TxSelf(SelfTx),
+    /// ICS-20 transfer to `to_address` on the chain at the other end of `channel_id`
+    IbcTransfer {
+        channel_id: String,
+        to_address: String,
+        amount: Coin,
+        timeout: TransferTimeout,
+    },
+    /// Votes on a chain governance proposal with the wallet's stake
+    GovVote {
+        proposal_id: u64,
+        vote: VoteOption,
+    },
+    /// Splits the wallet's voting power between options, the weights must add up to 1
+    GovVoteWeighted {
+        proposal_id: u64,
+        options: Vec<WeightedVoteOption>,
+    },
+    /// Lets `grantee` send messages on behalf of the wallet through authz. Generic grants
+    /// of messages moving funds are rejected while the allowlist mode or a rate limit is on.
+    AuthzGrant {
+        grantee: Addr,
+        authorization: Authorization,
+        expiration: Option<Timestamp>,
+    },
+    /// Revokes the authz grant for `msg_type_url`, `/cosmos.bank.v1beta1.MsgSend` for a
+    /// send authorization
+    AuthzRevoke {
+        grantee: Addr,
+        msg_type_url: String,
+    },
+    /// Pays the fees of `grantee` up to `spend_limit`, without limit when it's empty,
+    /// which isn't allowed while a rate limit is set
+    GrantAllowance {
+        grantee: Addr,
+        spend_limit: Vec<Coin>,
+        expiration: Option<Timestamp>,
+    },
+    RevokeAllowance {
+        grantee: Addr,
+    },
+    /// Messages proposed through the cw3 `Propose`, they are sent together and the
+    /// transaction stays pending if any of them fails
+    Messages {
+        msgs: Vec<CosmosMsg>,
+    },
+}
+
+/// Address on this chain and funds `msg` sends, for messages that move the wallet's funds
+pub fn msg_funds(msg: &CosmosMsg) -> Option<(&str, &[Coin])> {
+    match msg {
+        CosmosMsg::Bank(BankMsg::Send { to_address, amount }) => Some((to_address, amount)),
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr,
+            funds,
+            ..
+        }) => Some((contract_addr, funds)),
+        _ => None,
+    }
+}
+
+/// cw20 messages that let tokens leave the wallet, only their recipient and amount are read
+#[derive(Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum Cw20Msg {
+    Transfer { recipient: String, amount: Uint128 },
+    Send { contract: String, amount: Uint128 },
+    IncreaseAllowance { spender: String, amount: Uint128 },
+}
+
+/// Recipient and tokens `msg` moves, for executions of a cw20 transfer, send or allowance.
+/// The tokens are denominated by the cw20 contract address.
+pub fn cw20_transfer(msg: &CosmosMsg) -> Option<(String, Coin)> {
+    let CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr, msg, ..
+    }) = msg
+    else {
+        return None;
+    };
+    let (recipient, amount) = match from_json(msg).ok()? {
+        Cw20Msg::Transfer { recipient, amount } => (recipient, amount),
+        Cw20Msg::Send { contract, amount } => (contract, amount),
+        Cw20Msg::IncreaseAllowance { spender, amount } => (spender, amount),
+    };
+    Some((
+        recipient,
+        Coin {
+            denom: contract_addr.clone(),
+            amount,
+        },
+    ))
+}
+
+/// Messages the wallet can't tell the funds or the recipient of, e.g. Stargate or staking
+/// messages. Contract messages, IBC transfers and gov votes are understood.
+pub fn msg_unverifiable(msg: &CosmosMsg) -> bool {
+    !matches!(
+        msg,
+        CosmosMsg::Bank(BankMsg::Send { .. })
+            | CosmosMsg::Wasm(_)
+            | CosmosMsg::Ibc(IbcMsg::Transfer { .. })
+            | CosmosMsg::Gov(_)
+    )
+}
+
+/// Funds sent by `msgs` per denom, cw20 tokens per contract address
+pub fn msgs_outflow(msgs: &[CosmosMsg]) -> Vec<Coin> {
+    let mut outflow: Vec<Coin> = vec![];
+    let ibc_amounts = msgs.iter().filter_map(|msg| match msg {
+        CosmosMsg::Ibc(IbcMsg::Transfer { amount, .. }) => Some(amount),
+        _ => None,
+    });
+    let instantiate_funds = msgs.iter().flat_map(|msg| match msg {
+        CosmosMsg::Wasm(
+            WasmMsg::Instantiate { funds, .. } | WasmMsg::Instantiate2 { funds, .. },
+        ) => funds.as_slice(),
+        _ => &[],
+    });
+    let cw20_amounts: Vec<Coin> = msgs
+        .iter()
+        .filter_map(cw20_transfer)
+        .map(|(_, coin)| coin)
+        .collect();
+    let funds = msgs
+        .iter()
+        .filter_map(msg_funds)
+        .flat_map(|(_, funds)| funds)
+        .chain(ibc_amounts)
+        .chain(instantiate_funds)
+        .chain(&cw20_amounts);
+    for coin in funds {
+        match outflow.iter_mut().find(|total| total.denom == coin.denom) {
+            Some(total) => total.amount += coin.amount,
+            None => outflow.push(coin.clone()),
+        }
+    }
+    outflow
+}
+
+/// cw3 vote, only `yes` counts towards the signatures
+#[cw_serde]
+pub enum Vote {
+    Yes,
+    No,
+    Abstain,
+    Veto,
+}
+
+impl Display for Vote {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Vote::Yes => write!(f, "yes"),
+            Vote::No => write!(f, "no"),
+            Vote::Abstain => write!(f, "abstain"),
+            Vote::Veto => write!(f, "veto"),
+        }
+    }
+}
+
+/// Vote of an owner on a transaction
+#[cw_serde]
+pub struct Ballot {
+    pub vote: Vote,
+    pub weight: u64,
+}
+
+/// Details a transaction has as a cw3 proposal
+#[cw_serde]
+pub struct ProposalInfo {
+    pub title: String,
+    pub description: String,
+    pub proposer: Addr,
+    /// Votes are no longer accepted once it expires, a transaction that reached its
+    /// signatures can still be executed
+    pub expires: Expiration,
+}
+
+/// Permission an authz grant gives the grantee
+#[cw_serde]
+pub enum Authorization {
+    /// Sending up to `spend_limit` from the wallet
+    Send { spend_limit: Vec<Coin> },
+    /// Sending any message of `msg_type_url` on behalf of the wallet
+    Generic { msg_type_url: String },
+}
+
+impl Display for Authorization {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Authorization::Send { spend_limit } => write!(f, "send {}", spend_limit.to_string()),
+            Authorization::Generic { msg_type_url } => write!(f, "generic {}", msg_type_url),
+        }
+    }
+}
+
+impl Authorization {
+    /// Whether the grantee could move the wallet's funds with it, a generic grant of a
+    /// send, IBC transfer or contract execution has no spend limit at all
+    pub fn moves_funds(&self) -> bool {
+        match self {
+            Authorization::Send { .. } => true,
+            Authorization::Generic { msg_type_url } => matches!(
+                msg_type_url.as_str(),
+                "/cosmos.bank.v1beta1.MsgSend"
+                    | "/cosmos.bank.v1beta1.MsgMultiSend"
+                    | "/ibc.applications.transfer.v1.MsgTransfer"
+                    | "/cosmwasm.wasm.v1.MsgExecuteContract"
+            ),
+        }
+    }
+}
+
+/// Vote option as in JSON messages, e.g. `no_with_veto`
+pub fn vote_option_str(vote: &VoteOption) -> &'static str {
+    match vote {
+        VoteOption::Yes => "yes",
+        VoteOption::No => "no",
+        VoteOption::Abstain => "abstain",
+        VoteOption::NoWithVeto => "no_with_veto",
+    }
+}
+
+/// Weighted options as `option:weight`, e.g. `yes:0.7,abstain:0.3`
+pub fn weighted_votes_str(options: &[WeightedVoteOption]) -> String {
+    options
+        .iter()
+        .map(|option| format!("{}:{}", vote_option_str(&option.option), option.weight))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// When an IBC transfer times out and the funds return to the wallet
+#[cw_serde]
+pub enum TransferTimeout {
+    /// Block height of the destination chain
+    Height { revision: u64, height: u64 },
+    /// Seconds after the transaction is executed
+    Time { seconds: u64 },
+}
+
+impl TransferTimeout {
+    pub fn to_ibc_timeout(&self, env: &Env) -> IbcTimeout {
+        match self {
+            TransferTimeout::Height { revision, height } => {
+                IbcTimeout::with_block(IbcTimeoutBlock {
+                    revision: *revision,
+                    height: *height,
+                })
+            }
+            TransferTimeout::Time { seconds } => {
+                IbcTimeout::with_timestamp(env.block.time.plus_seconds(*seconds))
+            }
+        }
+    }
+}
+
+impl Display for TransferTimeout {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TransferTimeout::Height { revision, height } => {
+                write!(f, "height {}-{}", revision, height)
+            }
+            TransferTimeout::Time { seconds } => write!(f, "{}s", seconds),
+        }
+    }
 }
 
 #[cw_serde]
 pub enum SelfTx {
-    AddOwner { owner: Addr, quorum: Option<u32> },
-    RemoveOwner { owner: Addr, quorum: Option<u32> },
-    UpdateQuorum { quorum: u32 },
+    AddOwner {
+        owner: Addr,
+        quorum: Option<u32>,
+    },
+    RemoveOwner {
+        owner: Addr,
+        quorum: Option<u32>,
+    },
+    UpdateQuorum {
+        quorum: u32,
+    },
+    UpdateGuardians {
+        guardians: Option<GuardianSet>,
+    },
+    Unpause {},
+    SetPermissionlessExecution {
+        enabled: bool,
+    },
+    SetAutoExecute {
+        enabled: bool,
+    },
+    /// Pays `msg` `count` times, every `interval` seconds starting at `start`.
+    /// Only bank transfers of non-zero coins can be scheduled.
+    CreateSchedule {
+        msg: Box<TxMsg>,
+        start: Timestamp,
+        interval: u64,
+        count: u32,
+    },
+    CancelSchedule {
+        id: u64,
+    },
+    /// Releases `amount` to `recipient` linearly between `start` and `end`
+    CreateStream {
+        recipient: Addr,
+        amount: Coin,
+        start: Timestamp,
+        end: Timestamp,
+    },
+    /// Pays out the vested part of the stream and releases the rest, the vested part stays
+    /// in the treasury too if the recipient isn't allowed
+    CancelStream {
+        id: u64,
+    },
+    SetContact {
+        addr: Addr,
+        label: String,
+        tags: Vec<String>,
+    },
+    RemoveContact {
+        addr: Addr,
+    },
+    /// Gives a non-owner the proposer or executor role, owners hold every role
+    GrantRole {
+        addr: Addr,
+        role: Role,
+    },
+    RevokeRole {
+        addr: Addr,
+        role: Role,
+    },
+    /// Sets the signatures required by a category of transactions,
+    /// `None` makes the category follow the quorum again
+    SetThreshold {
+        category: TxCategory,
+        threshold: Option<u32>,
+    },
+    /// Sets the signatures required by bank transfers of `denom` depending on the amount,
+    /// an empty list removes the tiers
+    SetAmountTiers {
+        denom: String,
+        tiers: Vec<AmountTier>,
+    },
+    /// Caps the outflow of `denom` per rolling window, `None` removes the cap. cw20 tokens
+    /// sent through messages are capped by their contract address. Loosening an existing cap
+    /// only takes effect after the rate limit delay.
+    SetRateLimit {
+        denom: String,
+        limit: Option<RateLimit>,
+    },
+    /// Only lets funds go to allowlisted recipients while enabled
+    SetAllowlistMode {
+        enabled: bool,
+    },
+    AllowRecipients {
+        addrs: Vec<Addr>,
+    },
+    DisallowRecipients {
+        addrs: Vec<Addr>,
+    },
+    /// Contract checking every transaction before and after its execution, `None` removes it.
+    /// Changing the guard bypasses the current one so a faulty guard can't lock the wallet.
+    SetGuard {
+        guard: Option<Addr>,
+    },
+    /// Lets `module` execute messages from the wallet without a vote
+    EnableModule {
+        module: Addr,
+    },
+    DisableModule {
+        module: Addr,
+    },
+    /// Sends cw4 member-changed hook messages to `hook` when owners are added or removed
+    AddHook {
+        hook: Addr,
+    },
+    RemoveHook {
+        hook: Addr,
+    },
+}
+
+/// Maximum `amount` that can leave the wallet within `window` seconds
+#[cw_serde]
+pub struct RateLimit {
+    pub amount: Uint128,
+    pub window: u64,
+}
+
+impl RateLimit {
+    /// Whether `self` lets less or the same amount out as `other` in any window
+    pub fn is_stricter_than(&self, other: &RateLimit) -> bool {
+        self.amount <= other.amount && self.window >= other.window
+    }
+}
+
+/// Loosened rate limit waiting for its timelock
+#[cw_serde]
+pub struct PendingRateLimit {
+    pub limit: Option<RateLimit>,
+    pub effective_at: Timestamp,
+}
+
+/// Amount that left the wallet in a rate limited denom
+#[cw_serde]
+pub struct Outflow {
+    pub amount: Uint128,
+    pub at: Timestamp,
+}
+
+/// Signatures required by transfers of up to `up_to`, the last tier of a denom has no bound
+#[cw_serde]
+pub struct AmountTier {
+    pub up_to: Option<Uint128>,
+    pub threshold: u32,
+}
+
+impl Display for AmountTier {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.up_to {
+            Some(up_to) => write!(f, "{}:{}", up_to, self.threshold),
+            None => write!(f, "unbounded:{}", self.threshold),
+        }
+    }
+}
+
+/// Kinds of transactions that can require their own number of signatures
+#[cw_serde]
+pub enum TxCategory {
+    /// Bank and IBC transfers
+    Bank,
+    /// Changes to the owners, quorum, thresholds, rate limits, allowlist, guard, modules,
+    /// guardians and roles
+    Governance,
+    /// Every other change to the wallet itself
+    SelfConfig,
+    /// Votes on chain governance proposals
+    ChainVote,
+    /// Arbitrary messages proposed through cw3
+    Custom,
+}
+
+impl TxCategory {
+    pub const ALL: [TxCategory; 5] = [
+        TxCategory::Bank,
+        TxCategory::Governance,
+        TxCategory::SelfConfig,
+        TxCategory::ChainVote,
+        TxCategory::Custom,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TxCategory::Bank => "bank",
+            TxCategory::Governance => "governance",
+            TxCategory::SelfConfig => "self_config",
+            TxCategory::ChainVote => "chain_vote",
+            TxCategory::Custom => "custom",
+        }
+    }
+}
+
+impl Display for TxCategory {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+#[cw_serde]
+pub enum Role {
+    /// Can create transactions
+    Proposer,
+    /// Can sign transactions, this role comes with ownership
+    Signer,
+    /// Can execute transactions that reached quorum
+    Executor,
+}
+
+impl Display for Role {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Role::Proposer => write!(f, "proposer"),
+            Role::Signer => write!(f, "signer"),
+            Role::Executor => write!(f, "executor"),
+        }
+    }
+}
+
+/// Address book entry shown to signers next to the address
+#[cw_serde]
+pub struct Contact {
+    pub addr: Addr,
+    pub label: String,
+    pub tags: Vec<String>,
+}
+
+/// Recurring payment approved once by the owners
+#[cw_serde]
+pub struct Schedule {
+    pub id: u64,
+    pub msg: TxMsg,
+    pub start: Timestamp,
+    /// Seconds between two payments
+    pub interval: u64,
+    /// Total number of payments
+    pub count: u32,
+    /// Number of payments already made
+    pub executed: u32,
+}
+
+/// Linear payout of treasury funds, the unclaimed part stays reserved
+#[cw_serde]
+pub struct Stream {
+    pub id: u64,
+    pub recipient: Addr,
+    pub amount: Coin,
+    pub start: Timestamp,
+    pub end: Timestamp,
+    pub claimed: Uint128,
+}
+
+impl Stream {
+    /// Amount released to the recipient at `now`, including what was already claimed
+    pub fn vested(&self, now: Timestamp) -> Uint128 {
+        if now <= self.start {
+            return Uint128::zero();
+        }
+        if now >= self.end {
+            return self.amount.amount;
+        }
+
+        self.amount.amount.multiply_ratio(
+            now.seconds() - self.start.seconds(),
+            self.end.seconds() - self.start.seconds(),
+        )
+    }
+
+    pub fn claimable(&self, now: Timestamp) -> Uint128 {
+        self.vested(now) - self.claimed
+    }
+}
+
+impl Schedule {
+    /// Number of payments that are due at `now` and haven't been made yet
+    pub fn due(&self, now: Timestamp) -> u32 {
+        if now < self.start {
+            return 0;
+        }
+
+        let elapsed = (now.seconds() - self.start.seconds()) / self.interval;
+        let reached = (elapsed + 1).min(self.count as u64) as u32;
+        reached.saturating_sub(self.executed)
+    }
+}
+
+/// Addresses allowed to recover the wallet when the owners can no longer reach quorum
+#[cw_serde]
+pub struct GuardianSet {
+    pub guardians: Vec<Addr>,
+    /// Number of guardian approvals needed to execute a recovery
+    pub threshold: u32,
+    /// Seconds that must pass between proposing and executing a recovery
+    pub recovery_delay: u64,
+}
+
+/// Emergency pause triggered by a single owner
+#[cw_serde]
+pub struct PauseInfo {
+    pub paused_by: Addr,
+    pub expires_at: Timestamp,
+}
+
+/// Owner set replacement proposed by a guardian
+#[cw_serde]
+pub struct Recovery {
+    pub owners: Vec<Addr>,
+    pub quorum: u32,
+    pub approvals: Vec<Addr>,
+    pub proposed_at: Timestamp,
 }
 
 trait ToStr {
@@ -30,7 +624,10 @@ trait ToStr {
 
 impl ToStr for Vec<Coin> {
     fn to_string(&self) -> String {
-        self.iter().map(|coin| coin.to_string()).collect::<String>()
+        self.iter()
+            .map(|coin| coin.to_string())
+            .collect::<Vec<_>>()
+            .join(",")
     }
 }
 
@@ -40,13 +637,158 @@ impl Transaction {
             tx_msg,
             id,
             num_confirmations: 0,
+            status: TxStatus::Pending,
+            executor: None,
+            auto_execute: None,
         }
     }
 }
 
-impl ToString for Transaction {
-    fn to_string(&self) -> String {
-        format!("Transaction {{ tx_msg: {}, id: {} }}", self.tx_msg, self.id)
+impl Display for TxStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TxStatus::Pending => write!(f, "pending"),
+            TxStatus::Executed => write!(f, "executed"),
+            TxStatus::ExecutionFailed { .. } => write!(f, "execution_failed"),
+            TxStatus::Rejected => write!(f, "rejected"),
+        }
+    }
+}
+
+impl TxMsg {
+    /// Short name of the message kind, used in events
+    pub fn msg_type(&self) -> &'static str {
+        match self {
+            TxMsg::TxBank { .. } => "bank",
+            TxMsg::TxSelf(_) => "self",
+            TxMsg::IbcTransfer { .. } => "ibc_transfer",
+            TxMsg::GovVote { .. } | TxMsg::GovVoteWeighted { .. } => "gov_vote",
+            TxMsg::AuthzGrant { .. } | TxMsg::AuthzRevoke { .. } => "authz",
+            TxMsg::GrantAllowance { .. } | TxMsg::RevokeAllowance { .. } => "feegrant",
+            TxMsg::Messages { .. } => "messages",
+        }
+    }
+
+    /// Category deciding how many signatures the message needs
+    pub fn category(&self) -> TxCategory {
+        match self {
+            TxMsg::TxBank { .. } | TxMsg::IbcTransfer { .. } => TxCategory::Bank,
+            TxMsg::GovVote { .. } | TxMsg::GovVoteWeighted { .. } => TxCategory::ChainVote,
+            TxMsg::AuthzGrant { .. }
+            | TxMsg::AuthzRevoke { .. }
+            | TxMsg::GrantAllowance { .. }
+            | TxMsg::RevokeAllowance { .. } => TxCategory::Governance,
+            TxMsg::Messages { .. } => TxCategory::Custom,
+            TxMsg::TxSelf(
+                SelfTx::AddOwner { .. }
+                | SelfTx::RemoveOwner { .. }
+                | SelfTx::UpdateQuorum { .. }
+                | SelfTx::UpdateGuardians { .. }
+                | SelfTx::GrantRole { .. }
+                | SelfTx::RevokeRole { .. }
+                | SelfTx::SetThreshold { .. }
+                | SelfTx::SetAmountTiers { .. }
+                | SelfTx::SetRateLimit { .. }
+                | SelfTx::SetAllowlistMode { .. }
+                | SelfTx::AllowRecipients { .. }
+                | SelfTx::DisallowRecipients { .. }
+                | SelfTx::SetGuard { .. }
+                | SelfTx::EnableModule { .. }
+                | SelfTx::DisableModule { .. }
+                | SelfTx::AddHook { .. }
+                | SelfTx::RemoveHook { .. },
+            ) => TxCategory::Governance,
+            TxMsg::TxSelf(_) => TxCategory::SelfConfig,
+        }
+    }
+
+    /// Address on this chain receiving funds from this message, directly or through a
+    /// schedule or stream. Recipients of `Messages` are found with `msg_funds`.
+    pub fn recipient(&self) -> Option<&Addr> {
+        match self {
+            TxMsg::TxBank { to, .. } => Some(to),
+            TxMsg::TxSelf(SelfTx::CreateSchedule { msg, .. }) => msg.recipient(),
+            TxMsg::TxSelf(SelfTx::CreateStream { recipient, .. }) => Some(recipient),
+            TxMsg::TxSelf(_)
+            | TxMsg::IbcTransfer { .. }
+            | TxMsg::GovVote { .. }
+            | TxMsg::GovVoteWeighted { .. }
+            | TxMsg::AuthzGrant { .. }
+            | TxMsg::AuthzRevoke { .. }
+            | TxMsg::GrantAllowance { .. }
+            | TxMsg::RevokeAllowance { .. }
+            | TxMsg::Messages { .. } => None,
+        }
+    }
+
+    /// Funds leaving the wallet when this message is executed. Grants count their whole
+    /// spend limit, the grantee can spend it later outside of the wallet's control.
+    pub fn outflow(&self) -> Vec<Coin> {
+        match self {
+            TxMsg::TxBank { coins, .. } => coins.clone(),
+            TxMsg::IbcTransfer { amount, .. } => vec![amount.clone()],
+            TxMsg::Messages { msgs } => msgs_outflow(msgs),
+            TxMsg::AuthzGrant {
+                authorization: Authorization::Send { spend_limit },
+                ..
+            }
+            | TxMsg::GrantAllowance { spend_limit, .. } => spend_limit.clone(),
+            TxMsg::TxSelf(_)
+            | TxMsg::GovVote { .. }
+            | TxMsg::GovVoteWeighted { .. }
+            | TxMsg::AuthzGrant { .. }
+            | TxMsg::AuthzRevoke { .. }
+            | TxMsg::RevokeAllowance { .. } => vec![],
+        }
+    }
+
+    /// Funds the wallet commits to when this message is executed, which includes every
+    /// payment of a schedule or stream it creates
+    pub fn committed(&self) -> Vec<Coin> {
+        match self {
+            TxMsg::TxSelf(SelfTx::CreateSchedule { msg, count, .. }) => msg
+                .outflow()
+                .into_iter()
+                .map(|coin| Coin {
+                    denom: coin.denom,
+                    amount: coin.amount.saturating_mul(Uint128::from(*count)),
+                })
+                .collect(),
+            TxMsg::TxSelf(SelfTx::CreateStream { amount, .. }) => vec![amount.clone()],
+            _ => self.outflow(),
+        }
+    }
+
+    /// Displays the message along with the address book label of its recipient,
+    /// `contact` being `None` flags an unknown recipient
+    pub fn labeled<'a>(&'a self, contact: Option<&'a Contact>) -> LabeledTxMsg<'a> {
+        LabeledTxMsg { msg: self, contact }
+    }
+}
+
+pub struct LabeledTxMsg<'a> {
+    msg: &'a TxMsg,
+    contact: Option<&'a Contact>,
+}
+
+impl Display for LabeledTxMsg<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.msg)?;
+        match (self.msg.recipient(), self.contact) {
+            (None, _) => Ok(()),
+            (Some(_), Some(contact)) => write!(f, " [recipient: {}]", contact.label),
+            (Some(_), None) => write!(f, " [unknown recipient]"),
+        }
+    }
+}
+
+impl Display for Transaction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Transaction {{ tx_msg: {}, id: {} }}",
+            self.tx_msg, self.id
+        )
     }
 }
 
@@ -67,10 +809,176 @@ impl Display for TxMsg {
                 SelfTx::UpdateQuorum { quorum } => {
                     write!(f, "SelfTx-UpdateQuorum {{quorum: {}}}", quorum)
                 }
+                SelfTx::UpdateGuardians { guardians } => match guardians {
+                    Some(set) => write!(
+                        f,
+                        "SelfTx-UpdateGuardians {{guardians: [{}], threshold: {}, recovery_delay: {}}}",
+                        set.guardians
+                            .iter()
+                            .map(Addr::as_str)
+                            .collect::<Vec<_>>()
+                            .join(", "),
+                        set.threshold,
+                        set.recovery_delay
+                    ),
+                    None => write!(f, "SelfTx-UpdateGuardians {{guardians: None}}"),
+                },
+                SelfTx::Unpause {} => write!(f, "SelfTx-Unpause"),
+                SelfTx::SetPermissionlessExecution { enabled } => write!(
+                    f,
+                    "SelfTx-SetPermissionlessExecution {{enabled: {}}}",
+                    enabled
+                ),
+                SelfTx::SetAutoExecute { enabled } => {
+                    write!(f, "SelfTx-SetAutoExecute {{enabled: {}}}", enabled)
+                }
+                SelfTx::SetAllowlistMode { enabled } => {
+                    write!(f, "SelfTx-SetAllowlistMode {{enabled: {}}}", enabled)
+                }
+                SelfTx::AllowRecipients { addrs } => {
+                    write!(f, "SelfTx-AllowRecipients {{addrs: {:?}}}", addrs)
+                }
+                SelfTx::DisallowRecipients { addrs } => {
+                    write!(f, "SelfTx-DisallowRecipients {{addrs: {:?}}}", addrs)
+                }
+                SelfTx::SetGuard { guard } => {
+                    write!(f, "SelfTx-SetGuard {{guard: {:?}}}", guard)
+                }
+                SelfTx::EnableModule { module } => {
+                    write!(f, "SelfTx-EnableModule {{module: {}}}", module)
+                }
+                SelfTx::DisableModule { module } => {
+                    write!(f, "SelfTx-DisableModule {{module: {}}}", module)
+                }
+                SelfTx::AddHook { hook } => write!(f, "SelfTx-AddHook {{hook: {}}}", hook),
+                SelfTx::RemoveHook { hook } => write!(f, "SelfTx-RemoveHook {{hook: {}}}", hook),
+                SelfTx::CreateSchedule {
+                    msg,
+                    start,
+                    interval,
+                    count,
+                } => write!(
+                    f,
+                    "SelfTx-CreateSchedule {{msg: {}, start: {}, interval: {}, count: {}}}",
+                    msg, start, interval, count
+                ),
+                SelfTx::CancelSchedule { id } => {
+                    write!(f, "SelfTx-CancelSchedule {{id: {}}}", id)
+                }
+                SelfTx::CreateStream {
+                    recipient,
+                    amount,
+                    start,
+                    end,
+                } => write!(
+                    f,
+                    "SelfTx-CreateStream {{recipient: {}, amount: {}, start: {}, end: {}}}",
+                    recipient, amount, start, end
+                ),
+                SelfTx::CancelStream { id } => write!(f, "SelfTx-CancelStream {{id: {}}}", id),
+                SelfTx::SetContact { addr, label, tags } => write!(
+                    f,
+                    "SelfTx-SetContact {{addr: {}, label: {}, tags: [{}]}}",
+                    addr,
+                    label,
+                    tags.join(", ")
+                ),
+                SelfTx::RemoveContact { addr } => {
+                    write!(f, "SelfTx-RemoveContact {{addr: {}}}", addr)
+                }
+                SelfTx::GrantRole { addr, role } => {
+                    write!(f, "SelfTx-GrantRole {{addr: {}, role: {}}}", addr, role)
+                }
+                SelfTx::RevokeRole { addr, role } => {
+                    write!(f, "SelfTx-RevokeRole {{addr: {}, role: {}}}", addr, role)
+                }
+                SelfTx::SetThreshold {
+                    category,
+                    threshold,
+                } => write!(
+                    f,
+                    "SelfTx-SetThreshold {{category: {}, threshold: {:?}}}",
+                    category, threshold
+                ),
+                SelfTx::SetRateLimit { denom, limit } => match limit {
+                    Some(limit) => write!(
+                        f,
+                        "SelfTx-SetRateLimit {{denom: {}, amount: {}, window: {}}}",
+                        denom, limit.amount, limit.window
+                    ),
+                    None => write!(f, "SelfTx-SetRateLimit {{denom: {}, limit: None}}", denom),
+                },
+                SelfTx::SetAmountTiers { denom, tiers } => write!(
+                    f,
+                    "SelfTx-SetAmountTiers {{denom: {}, tiers: {}}}",
+                    denom,
+                    tiers
+                        .iter()
+                        .map(AmountTier::to_string)
+                        .collect::<Vec<_>>()
+                        .join(",")
+                ),
             },
             TxMsg::TxBank { to, coins } => {
                 write!(f, "BankTx {{to: {}, coin: {}}}", to, coins.to_string())
             }
+            TxMsg::IbcTransfer {
+                channel_id,
+                to_address,
+                amount,
+                timeout,
+            } => write!(
+                f,
+                "IbcTransfer {{channel: {}, to: {}, amount: {}, timeout: {}}}",
+                channel_id, to_address, amount, timeout
+            ),
+            TxMsg::GovVote { proposal_id, vote } => write!(
+                f,
+                "GovVote {{proposal: {}, vote: {}}}",
+                proposal_id,
+                vote_option_str(vote)
+            ),
+            TxMsg::GovVoteWeighted {
+                proposal_id,
+                options,
+            } => write!(
+                f,
+                "GovVoteWeighted {{proposal: {}, options: {}}}",
+                proposal_id,
+                weighted_votes_str(options)
+            ),
+            TxMsg::AuthzGrant {
+                grantee,
+                authorization,
+                expiration,
+            } => write!(
+                f,
+                "AuthzGrant {{grantee: {}, authorization: {}, expiration: {:?}}}",
+                grantee, authorization, expiration
+            ),
+            TxMsg::AuthzRevoke {
+                grantee,
+                msg_type_url,
+            } => write!(
+                f,
+                "AuthzRevoke {{grantee: {}, msg_type_url: {}}}",
+                grantee, msg_type_url
+            ),
+            TxMsg::GrantAllowance {
+                grantee,
+                spend_limit,
+                expiration,
+            } => write!(
+                f,
+                "GrantAllowance {{grantee: {}, spend_limit: {}, expiration: {:?}}}",
+                grantee,
+                spend_limit.to_string(),
+                expiration
+            ),
+            TxMsg::RevokeAllowance { grantee } => {
+                write!(f, "RevokeAllowance {{grantee: {}}}", grantee)
+            }
+            TxMsg::Messages { msgs } => write!(f, "Messages {{count: {}}}", msgs.len()),
         }
     }
 }
@@ -84,7 +992,7 @@ impl PendingTransactions {
     }
 
     pub fn index(&self, index: u128) -> Option<&Transaction> {
-        self.0.iter().nth(index as usize)
+        self.0.get(index as usize)
     }
 
     pub fn push(&mut self, tx: Transaction) {
@@ -100,15 +1008,14 @@ impl PendingTransactions {
     }
 
     pub fn remove(&mut self, tx: &Transaction) -> Option<Transaction> {
-        let index = self.0.iter().position(|item|item.id==tx.id);
+        let index = self.0.iter().position(|item| item.id == tx.id);
         match index {
             Some(i) => {
                 let ret_tx: Transaction = self.0.remove(i);
                 Some(ret_tx)
-            },
-            None => None
+            }
+            None => None,
         }
-
     }
 }
 
@@ -121,7 +1028,7 @@ impl CompletedTransactions {
     }
 
     pub fn index(&self, index: u128) -> Option<&Transaction> {
-        self.0.iter().nth(index as usize)
+        self.0.get(index as usize)
     }
 
     pub fn push(&mut self, tx: Transaction) {
@@ -135,11 +1042,58 @@ impl CompletedTransactions {
     pub fn find(&self, tx_id: u128) -> Option<&Transaction> {
         self.0.iter().find(|tx| tx.id == tx_id)
     }
+
+    pub fn remove(&mut self, tx_id: u128) -> Option<Transaction> {
+        let index = self.0.iter().position(|item| item.id == tx_id)?;
+        Some(self.0.remove(index))
+    }
 }
 
 pub const ADMINS: Item<Vec<Addr>> = Item::new("admins");
+/// cw4 group the owners and their weights are read from, `ADMINS` is empty while it's set
+pub const GROUP: Item<Addr> = Item::new("group");
 pub const QUORUM: Item<u32> = Item::new("quorum");
 pub const TX_COUNTER: Item<u128> = Item::new("tx_counter");
 pub const PENDING_TXS: Item<PendingTransactions> = Item::new("pending_txs");
 pub const COMPLETED_TXS: Item<CompletedTransactions> = Item::new("completed_txs");
 pub const SIGNED_TX: Map<(Addr, u128), bool> = Map::new("signed_tx");
+/// Every vote cast on a transaction, including the signatures
+pub const BALLOTS: Map<(u128, &Addr), Ballot> = Map::new("ballots");
+pub const PROPOSALS: Map<u128, ProposalInfo> = Map::new("proposals");
+pub const TEMP_EXECUTING_TX: Item<u128> = Item::new("executing_tx_temp");
+pub const GUARDIANS: Item<GuardianSet> = Item::new("guardians");
+pub const RECOVERY: Item<Recovery> = Item::new("recovery");
+pub const PAUSE_DURATION: Item<u64> = Item::new("pause_duration");
+pub const PAUSE: Item<PauseInfo> = Item::new("pause");
+/// Earliest time the wallet can be paused again
+pub const PAUSE_COOLDOWN: Item<Timestamp> = Item::new("pause_cooldown");
+pub const PERMISSIONLESS_EXECUTION: Item<bool> = Item::new("permissionless_execution");
+pub const AUTO_EXECUTE: Item<bool> = Item::new("auto_execute");
+pub const TEMP_AUTO_EXECUTE_TX: Item<u128> = Item::new("auto_execute_tx_temp");
+pub const SCHEDULE_COUNTER: Item<u64> = Item::new("schedule_counter");
+pub const SCHEDULES: Map<u64, Schedule> = Map::new("schedules");
+pub const STREAM_COUNTER: Item<u64> = Item::new("stream_counter");
+pub const STREAMS: Map<u64, Stream> = Map::new("streams");
+/// Unclaimed stream funds per denom, not available to other transfers
+pub const STREAM_RESERVED: Map<&str, Uint128> = Map::new("stream_reserved");
+pub const CONTACTS: Map<&Addr, Contact> = Map::new("contacts");
+/// Roles granted to addresses that aren't owners
+pub const ROLES: Map<&Addr, Vec<Role>> = Map::new("roles");
+/// Signatures required per `TxCategory`, categories without one use the quorum
+pub const THRESHOLDS: Map<&str, u32> = Map::new("thresholds");
+/// Amount tiers of bank transfers per denom, sorted by `up_to`
+pub const AMOUNT_TIERS: Map<&str, Vec<AmountTier>> = Map::new("amount_tiers");
+/// Seconds before a loosened rate limit takes effect
+pub const RATE_LIMIT_DELAY: Item<u64> = Item::new("rate_limit_delay");
+pub const RATE_LIMITS: Map<&str, RateLimit> = Map::new("rate_limits");
+pub const PENDING_RATE_LIMITS: Map<&str, PendingRateLimit> = Map::new("pending_rate_limits");
+/// Outflows of rate limited denoms within their current window
+pub const OUTFLOWS: Map<&str, Vec<Outflow>> = Map::new("outflows");
+pub const ALLOWLIST_MODE: Item<bool> = Item::new("allowlist_mode");
+/// Recipients funds can go to while the allowlist mode is enabled
+pub const ALLOWLIST: Map<&Addr, bool> = Map::new("allowlist");
+pub const GUARD: Item<Addr> = Item::new("guard");
+/// Contracts allowed to execute messages from the wallet without a vote
+pub const MODULES: Map<&Addr, bool> = Map::new("modules");
+/// Contracts notified when the owners change
+pub const HOOKS: Map<&Addr, bool> = Map::new("hooks");
@@ -0,0 +1,386 @@
+//! Helpers shared by the integration tests. Each test crate only uses part of them.
+#![allow(dead_code)]
+
+use cosmwasm_std::testing::{MockApi, MockStorage};
+use cosmwasm_std::{coins, Addr, Empty, StdResult, Uint128};
+use cw_multi_test::{
+    App, AppBuilder, AppResponse, BankKeeper, Contract, ContractWrapper, DistributionKeeper,
+    Executor, FailingModule, Gov, GovFailingModule, IbcFailingModule, StakeKeeper, WasmKeeper,
+};
+use multisig::contract::{execute, instantiate, query, reply};
+use multisig::msg::{ExecuteMsg, InstantiateMsg, ListCompletedTxsResp, ListPendingResp, QueryMsg};
+use multisig::state::{Transaction, TxMsg};
+use multisig::ContractError;
+use serde::de::DeserializeOwned;
+
+pub const DENOM: &str = "usei";
+pub const OWNERS: [&str; 3] = ["owner1", "owner2", "owner3"];
+/// Funds the wallet receives when it's instantiated
+pub const WALLET_FUNDS: u128 = 1000;
+/// Account that instantiates and funds the wallets
+const FUNDER: &str = "funder";
+
+pub fn addr(addr: &str) -> Addr {
+    Addr::unchecked(addr)
+}
+
+pub fn multisig_contract() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new(execute, instantiate, query).with_reply(reply))
+}
+
+/// 2 of 3 wallet of the `OWNERS`
+pub fn instantiate_msg() -> InstantiateMsg {
+    InstantiateMsg {
+        owners: OWNERS.iter().map(|owner| addr(owner)).collect(),
+        quorum: 2,
+        group: None,
+        guardians: None,
+        pause_duration: None,
+        permissionless_execution: None,
+        auto_execute: None,
+        rate_limit_delay: None,
+        allowlist: None,
+    }
+}
+
+pub fn bank_tx(to: &str, amount: u128) -> TxMsg {
+    TxMsg::TxBank {
+        to: addr(to),
+        coins: coins(amount, DENOM),
+    }
+}
+
+/// multi-test chain whose governance module is `G`, multi-test's `App` by default
+pub type GovApp<G = GovFailingModule> = App<
+    BankKeeper,
+    MockApi,
+    MockStorage,
+    FailingModule<Empty, Empty, Empty>,
+    WasmKeeper<Empty, Empty>,
+    StakeKeeper,
+    DistributionKeeper,
+    IbcFailingModule,
+    G,
+>;
+
+/// Wallet deployed on a multi-test chain, with typed wrappers around its messages.
+/// Execute errors are the wallet's `ContractError`.
+pub struct MultisigApp<G = GovFailingModule> {
+    pub app: GovApp<G>,
+    pub code_id: u64,
+    pub wallet: Addr,
+}
+
+impl MultisigApp {
+    /// 2 of 3 wallet holding `WALLET_FUNDS`
+    pub fn new() -> Self {
+        Self::with_msg(instantiate_msg())
+    }
+
+    pub fn with_msg(msg: InstantiateMsg) -> Self {
+        Self::build(GovFailingModule::new(), msg)
+    }
+}
+
+impl<G: Gov> MultisigApp<G> {
+    /// 2 of 3 wallet on a chain whose governance module is `gov`
+    pub fn with_gov(gov: G) -> Self {
+        Self::build(gov, instantiate_msg())
+    }
+
+    fn build(gov: G, msg: InstantiateMsg) -> Self {
+        let mut app = AppBuilder::new().with_gov(gov).build(|router, _, storage| {
+            router
+                .bank
+                .init_balance(storage, &addr(FUNDER), coins(100 * WALLET_FUNDS, DENOM))
+                .unwrap();
+        });
+        let code_id = app.store_code(multisig_contract());
+
+        let mut multisig = Self {
+            app,
+            code_id,
+            wallet: Addr::unchecked(""),
+        };
+        multisig.wallet = multisig.instantiate(&msg).unwrap();
+        multisig
+    }
+
+    /// Instantiates another wallet of the same code, funded like the first one
+    pub fn instantiate(&mut self, msg: &InstantiateMsg) -> Result<Addr, ContractError> {
+        self.app
+            .instantiate_contract(
+                self.code_id,
+                addr(FUNDER),
+                msg,
+                &coins(WALLET_FUNDS, DENOM),
+                "Multisig",
+                None,
+            )
+            .map_err(contract_error)
+    }
+
+    pub fn execute(
+        &mut self,
+        sender: &str,
+        msg: &ExecuteMsg,
+    ) -> Result<AppResponse, ContractError> {
+        self.app
+            .execute_contract(addr(sender), self.wallet.clone(), msg, &[])
+            .map_err(contract_error)
+    }
+
+    /// Proposes `tx_msg` and returns its id
+    pub fn propose(&mut self, sender: &str, tx_msg: TxMsg) -> Result<u128, ContractError> {
+        let msg = ExecuteMsg::CreateTransaction {
+            tx_msg,
+            auto_execute: None,
+        };
+        self.execute(sender, &msg)?;
+        Ok(self.last_tx_id())
+    }
+
+    pub fn sign(&mut self, sender: &str, tx_id: u128) -> Result<AppResponse, ContractError> {
+        self.execute(sender, &ExecuteMsg::SignTransactions { tx_id })
+    }
+
+    pub fn execute_tx(&mut self, sender: &str, tx_id: u128) -> Result<AppResponse, ContractError> {
+        self.execute(sender, &ExecuteMsg::ExecuteTransaction { tx_id })
+    }
+
+    /// Proposes `tx_msg` as owner1, signs it as owner2 and executes it as owner1 unless the
+    /// wallet auto-executed it
+    pub fn approve(&mut self, tx_msg: TxMsg) -> Result<AppResponse, ContractError> {
+        let tx_id = self.propose(OWNERS[0], tx_msg)?;
+        let res = self.sign(OWNERS[1], tx_id)?;
+        if self.pending().iter().all(|tx| tx.id != tx_id) {
+            return Ok(res);
+        }
+        self.execute_tx(OWNERS[0], tx_id)
+    }
+
+    pub fn query<T: DeserializeOwned>(&self, msg: &QueryMsg) -> StdResult<T> {
+        self.app.wrap().query_wasm_smart(&self.wallet, msg)
+    }
+
+    pub fn pending(&self) -> Vec<Transaction> {
+        let resp: ListPendingResp = self.query(&QueryMsg::ListPending {}).unwrap();
+        resp.transactions.0
+    }
+
+    pub fn completed(&self) -> Vec<Transaction> {
+        let resp: ListCompletedTxsResp = self
+            .query(&QueryMsg::ListCompletedTxs {
+                offset: None,
+                limit: None,
+            })
+            .unwrap();
+        resp.transactions.0
+    }
+
+    /// Pending transaction `tx_id`
+    pub fn tx(&self, tx_id: u128) -> Transaction {
+        self.pending()
+            .into_iter()
+            .find(|tx| tx.id == tx_id)
+            .unwrap_or_else(|| panic!("transaction {tx_id} isn't pending"))
+    }
+
+    /// Id of the last created transaction, whether it's pending or completed
+    pub fn last_tx_id(&self) -> u128 {
+        self.pending()
+            .iter()
+            .chain(self.completed().iter())
+            .map(|tx| tx.id)
+            .max()
+            .unwrap_or_default()
+    }
+
+    pub fn balance(&self, addr: impl Into<String>) -> u128 {
+        self.app
+            .wrap()
+            .query_balance(addr, DENOM)
+            .map(|coin| coin.amount)
+            .unwrap_or(Uint128::zero())
+            .u128()
+    }
+
+    pub fn wallet_balance(&self) -> u128 {
+        self.balance(&self.wallet)
+    }
+
+    pub fn advance(&mut self, seconds: u64) {
+        self.app.update_block(|block| {
+            block.time = block.time.plus_seconds(seconds);
+            block.height += seconds / 5;
+        });
+    }
+
+    pub fn store_code(&mut self, code: Box<dyn Contract<Empty>>) -> u64 {
+        self.app.store_code(code)
+    }
+
+    /// Instantiates a contract of `code_id` that isn't a wallet
+    pub fn instantiate_other(&mut self, code_id: u64, msg: &impl serde::Serialize) -> Addr {
+        self.app
+            .instantiate_contract(code_id, addr(FUNDER), msg, &[], "Other", None)
+            .unwrap()
+    }
+}
+
+/// The wallet's error, multi-test wraps it in context
+pub fn contract_error(err: anyhow::Error) -> ContractError {
+    match err.downcast::<ContractError>() {
+        Ok(err) => err,
+        Err(err) => panic!("not a wallet error: {err:?}"),
+    }
+}
+
+/// Whether `res` has the `multisig.*` event `ty` with `action` and the `attributes`
+pub fn has_event(res: &AppResponse, ty: &str, action: &str, attributes: &[(&str, &str)]) -> bool {
+    res.events.iter().any(|event| {
+        event.ty == format!("wasm-{ty}")
+            && [("action", action)]
+                .iter()
+                .chain(attributes)
+                .all(|(key, value)| {
+                    event
+                        .attributes
+                        .iter()
+                        .any(|attr| attr.key == *key && attr.value == *value)
+                })
+    })
+}
+
+/// cw4 group keeping its members in storage, `UpdateMembers` notifies the hooks like
+/// cw4-group does
+pub mod mock_group {
+    use cosmwasm_schema::cw_serde;
+    use cosmwasm_std::{
+        to_json_binary, Binary, Deps, DepsMut, Env, MessageInfo, Order, Response, StdError,
+        StdResult, WasmMsg,
+    };
+    use cw_storage_plus::{Item, Map};
+    use multisig::msg::{
+        GroupQueryMsg, HookExecuteMsg, ListMembersResp, Member, MemberDiff, MemberResp,
+        TotalWeightResp,
+    };
+
+    const ADMIN: Item<String> = Item::new("admin");
+    const MEMBERS: Map<&str, u64> = Map::new("members");
+    const HOOKS: Item<Vec<String>> = Item::new("hooks");
+
+    #[cw_serde]
+    pub struct InstantiateMsg {
+        pub admin: String,
+        pub members: Vec<Member>,
+    }
+
+    #[cw_serde]
+    pub enum ExecuteMsg {
+        AddHook {
+            addr: String,
+        },
+        UpdateMembers {
+            remove: Vec<String>,
+            add: Vec<Member>,
+        },
+    }
+
+    pub fn instantiate(
+        deps: DepsMut,
+        _env: Env,
+        _info: MessageInfo,
+        msg: InstantiateMsg,
+    ) -> StdResult<Response> {
+        ADMIN.save(deps.storage, &msg.admin)?;
+        HOOKS.save(deps.storage, &vec![])?;
+        for member in msg.members {
+            MEMBERS.save(deps.storage, &member.addr, &member.weight)?;
+        }
+        Ok(Response::new())
+    }
+
+    pub fn execute(
+        deps: DepsMut,
+        _env: Env,
+        info: MessageInfo,
+        msg: ExecuteMsg,
+    ) -> StdResult<Response> {
+        if info.sender != ADMIN.load(deps.storage)? {
+            return Err(StdError::generic_err("not the group's admin"));
+        }
+
+        match msg {
+            ExecuteMsg::AddHook { addr } => {
+                HOOKS.update(deps.storage, |mut hooks| -> StdResult<_> {
+                    hooks.push(addr);
+                    Ok(hooks)
+                })?;
+                Ok(Response::new())
+            }
+            ExecuteMsg::UpdateMembers { remove, add } => {
+                let mut diffs = vec![];
+                for key in remove {
+                    let old = MEMBERS.may_load(deps.storage, &key)?;
+                    MEMBERS.remove(deps.storage, &key);
+                    diffs.push(MemberDiff {
+                        key,
+                        old,
+                        new: None,
+                    });
+                }
+                for member in add {
+                    let old = MEMBERS.may_load(deps.storage, &member.addr)?;
+                    MEMBERS.save(deps.storage, &member.addr, &member.weight)?;
+                    diffs.push(MemberDiff {
+                        key: member.addr,
+                        old,
+                        new: Some(member.weight),
+                    });
+                }
+
+                let msg = to_json_binary(&HookExecuteMsg::MemberChangedHook { diffs })?;
+                let msgs = HOOKS
+                    .load(deps.storage)?
+                    .into_iter()
+                    .map(|hook| WasmMsg::Execute {
+                        contract_addr: hook,
+                        msg: msg.clone(),
+                        funds: vec![],
+                    });
+                Ok(Response::new().add_messages(msgs))
+            }
+        }
+    }
+
+    pub fn query(deps: Deps, _env: Env, msg: GroupQueryMsg) -> StdResult<Binary> {
+        match msg {
+            GroupQueryMsg::Member { addr, .. } => to_json_binary(&MemberResp {
+                weight: MEMBERS.may_load(deps.storage, &addr)?,
+            }),
+            GroupQueryMsg::ListMembers { .. } => {
+                let members = MEMBERS
+                    .range(deps.storage, None, None, Order::Ascending)
+                    .map(|item| item.map(|(addr, weight)| Member { addr, weight }))
+                    .collect::<StdResult<_>>()?;
+                to_json_binary(&ListMembersResp { members })
+            }
+            GroupQueryMsg::TotalWeight { .. } => {
+                let weight = MEMBERS
+                    .range(deps.storage, None, None, Order::Ascending)
+                    .map(|item| item.map(|(_, weight)| weight))
+                    .sum::<StdResult<_>>()?;
+                to_json_binary(&TotalWeightResp { weight })
+            }
+        }
+    }
+
+    pub fn contract() -> Box<dyn cw_multi_test::Contract<cosmwasm_std::Empty>> {
+        Box::new(cw_multi_test::ContractWrapper::new(
+            execute,
+            instantiate,
+            query,
+        ))
+    }
+}
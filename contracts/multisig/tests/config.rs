@@ -0,0 +1,513 @@
+use cosmwasm_std::{Coin, Uint128};
+use multisig::msg::{
+    AmountTiersResp, CategoryThreshold, ConfigResp, ListAdminsResp, ListAllowlistResp,
+    ListContactsResp, ListPendingResp, QueryMsg, ThresholdsResp,
+};
+use multisig::state::{AmountTier, Contact, SelfTx, TxCategory, TxMsg};
+use multisig::ContractError;
+
+mod common;
+use common::{addr, bank_tx, has_event, instantiate_msg, MultisigApp, DENOM, OWNERS};
+
+fn admins(multisig: &MultisigApp) -> Vec<String> {
+    let resp: ListAdminsResp = multisig.query(&QueryMsg::ListAdmins {}).unwrap();
+    resp.admins.into_iter().map(String::from).collect()
+}
+
+fn quorum(multisig: &MultisigApp, tx_id: Option<u128>) -> u32 {
+    multisig.query(&QueryMsg::GetQuorum { tx_id }).unwrap()
+}
+
+fn tier(up_to: Option<u128>, threshold: u32) -> AmountTier {
+    AmountTier {
+        up_to: up_to.map(Uint128::new),
+        threshold,
+    }
+}
+
+#[test]
+fn test_add_owner() {
+    let mut multisig = MultisigApp::new();
+
+    let err = multisig
+        .approve(TxMsg::TxSelf(SelfTx::AddOwner {
+            owner: addr("owner4"),
+            quorum: Some(5),
+        }))
+        .unwrap_err();
+    assert_eq!(
+        ContractError::WrongQuorum {
+            quorum: 5,
+            owners: 4
+        },
+        err
+    );
+
+    let res = multisig
+        .approve(TxMsg::TxSelf(SelfTx::AddOwner {
+            owner: addr("owner4"),
+            quorum: Some(3),
+        }))
+        .unwrap();
+
+    assert!(has_event(
+        &res,
+        "multisig.owner",
+        "add",
+        &[("owner", "owner4")]
+    ));
+    assert!(has_event(
+        &res,
+        "multisig.config",
+        "update_quorum",
+        &[("quorum", "3")]
+    ));
+    assert_eq!(
+        vec!["owner1", "owner2", "owner3", "owner4"],
+        admins(&multisig)
+    );
+    assert_eq!(3, quorum(&multisig, None));
+}
+
+#[test]
+fn test_remove_owner() {
+    let mut multisig = MultisigApp::new();
+
+    let err = multisig
+        .approve(TxMsg::TxSelf(SelfTx::RemoveOwner {
+            owner: addr(OWNERS[2]),
+            quorum: Some(3),
+        }))
+        .unwrap_err();
+    assert_eq!(
+        ContractError::WrongQuorum {
+            quorum: 3,
+            owners: 2
+        },
+        err
+    );
+
+    let res = multisig
+        .approve(TxMsg::TxSelf(SelfTx::RemoveOwner {
+            owner: addr(OWNERS[2]),
+            quorum: Some(1),
+        }))
+        .unwrap();
+
+    assert!(has_event(
+        &res,
+        "multisig.owner",
+        "remove",
+        &[("owner", OWNERS[2])]
+    ));
+    assert_eq!(vec!["owner1", "owner2"], admins(&multisig));
+    assert_eq!(1, quorum(&multisig, None));
+    assert_eq!(
+        ContractError::Unauthorized {},
+        multisig
+            .propose(OWNERS[2], bank_tx("recipient", 5))
+            .unwrap_err()
+    );
+}
+
+#[test]
+fn test_update_quorum() {
+    let mut multisig = MultisigApp::new();
+
+    multisig
+        .approve(TxMsg::TxSelf(SelfTx::UpdateQuorum { quorum: 3 }))
+        .unwrap();
+    assert_eq!(3, quorum(&multisig, None));
+
+    let tx_id = multisig
+        .propose(OWNERS[0], TxMsg::TxSelf(SelfTx::UpdateQuorum { quorum: 4 }))
+        .unwrap();
+    multisig.sign(OWNERS[1], tx_id).unwrap();
+    multisig.sign(OWNERS[2], tx_id).unwrap();
+    assert_eq!(
+        ContractError::WrongQuorum {
+            quorum: 4,
+            owners: 3
+        },
+        multisig.execute_tx(OWNERS[0], tx_id).unwrap_err()
+    );
+}
+
+#[test]
+fn test_thresholds() {
+    let mut multisig = MultisigApp::new();
+
+    multisig
+        .approve(TxMsg::TxSelf(SelfTx::SetThreshold {
+            category: TxCategory::Bank,
+            threshold: Some(3),
+        }))
+        .unwrap();
+
+    let thresholds: ThresholdsResp = multisig.query(&QueryMsg::GetThresholds {}).unwrap();
+    assert_eq!(
+        CategoryThreshold {
+            category: TxCategory::Bank,
+            threshold: 3,
+            custom: true,
+        },
+        thresholds.thresholds[0]
+    );
+    assert_eq!(
+        CategoryThreshold {
+            category: TxCategory::Governance,
+            threshold: 2,
+            custom: false,
+        },
+        thresholds.thresholds[1]
+    );
+
+    let tx_id = multisig
+        .propose(OWNERS[0], bank_tx("recipient", 5))
+        .unwrap();
+    multisig.sign(OWNERS[1], tx_id).unwrap();
+    assert_eq!(3, quorum(&multisig, Some(tx_id)));
+    assert_eq!(
+        ContractError::NotEnoughSignatures {
+            quorum: 3,
+            num_signed: 2
+        },
+        multisig.execute_tx(OWNERS[0], tx_id).unwrap_err()
+    );
+    multisig.sign(OWNERS[2], tx_id).unwrap();
+    multisig.execute_tx(OWNERS[0], tx_id).unwrap();
+
+    // Owners can't be removed below a category's threshold
+    let err = multisig
+        .approve(TxMsg::TxSelf(SelfTx::RemoveOwner {
+            owner: addr(OWNERS[2]),
+            quorum: None,
+        }))
+        .unwrap_err();
+    assert_eq!(
+        ContractError::WrongThreshold {
+            category: TxCategory::Bank,
+            threshold: 3,
+            owners: 2
+        },
+        err
+    );
+
+    let res = multisig
+        .approve(TxMsg::TxSelf(SelfTx::SetThreshold {
+            category: TxCategory::Bank,
+            threshold: None,
+        }))
+        .unwrap();
+    assert!(has_event(
+        &res,
+        "multisig.config",
+        "set_threshold",
+        &[("category", "bank"), ("threshold", "none")]
+    ));
+    let tx_id = multisig
+        .propose(OWNERS[0], bank_tx("recipient", 5))
+        .unwrap();
+    assert_eq!(2, quorum(&multisig, Some(tx_id)));
+}
+
+#[test]
+fn test_thresholds_invalid() {
+    let mut multisig = MultisigApp::new();
+
+    for threshold in [0, 4] {
+        let err = multisig
+            .approve(TxMsg::TxSelf(SelfTx::SetThreshold {
+                category: TxCategory::ChainVote,
+                threshold: Some(threshold),
+            }))
+            .unwrap_err();
+        assert_eq!(
+            ContractError::WrongThreshold {
+                category: TxCategory::ChainVote,
+                threshold,
+                owners: 3
+            },
+            err
+        );
+    }
+}
+
+#[test]
+fn test_amount_tiers() {
+    let mut multisig = MultisigApp::new();
+
+    multisig
+        .approve(TxMsg::TxSelf(SelfTx::SetAmountTiers {
+            denom: DENOM.to_string(),
+            tiers: vec![tier(Some(100), 1), tier(None, 3)],
+        }))
+        .unwrap();
+    let resp: AmountTiersResp = multisig
+        .query(&QueryMsg::GetAmountTiers {
+            denom: DENOM.to_string(),
+        })
+        .unwrap();
+    assert_eq!(vec![tier(Some(100), 1), tier(None, 3)], resp.tiers);
+
+    // The tier can't lower the quorum
+    let small = multisig
+        .propose(OWNERS[0], bank_tx("recipient", 100))
+        .unwrap();
+    assert_eq!(2, quorum(&multisig, Some(small)));
+    let large = multisig
+        .propose(OWNERS[0], bank_tx("recipient", 101))
+        .unwrap();
+    assert_eq!(3, quorum(&multisig, Some(large)));
+
+    multisig.sign(OWNERS[1], large).unwrap();
+    assert_eq!(
+        ContractError::NotEnoughSignatures {
+            quorum: 3,
+            num_signed: 2
+        },
+        multisig.execute_tx(OWNERS[0], large).unwrap_err()
+    );
+    multisig.sign(OWNERS[2], large).unwrap();
+    multisig.execute_tx(OWNERS[0], large).unwrap();
+    assert_eq!(101, multisig.balance("recipient"));
+
+    // Schedules and streams count all of their payments
+    let now = multisig.app.block_info().time;
+    let schedule = |count| {
+        TxMsg::TxSelf(SelfTx::CreateSchedule {
+            msg: Box::new(bank_tx("recipient", 10)),
+            start: now,
+            interval: 100,
+            count,
+        })
+    };
+    let small = multisig.propose(OWNERS[0], schedule(10)).unwrap();
+    assert_eq!(2, quorum(&multisig, Some(small)));
+    let large = multisig.propose(OWNERS[0], schedule(11)).unwrap();
+    assert_eq!(3, quorum(&multisig, Some(large)));
+    let stream = multisig
+        .propose(
+            OWNERS[0],
+            TxMsg::TxSelf(SelfTx::CreateStream {
+                recipient: addr("recipient"),
+                amount: Coin::new(101, DENOM),
+                start: now,
+                end: now.plus_seconds(100),
+            }),
+        )
+        .unwrap();
+    assert_eq!(3, quorum(&multisig, Some(stream)));
+
+    // An empty list removes the tiers
+    let res = multisig
+        .approve(TxMsg::TxSelf(SelfTx::SetAmountTiers {
+            denom: DENOM.to_string(),
+            tiers: vec![],
+        }))
+        .unwrap();
+    assert!(has_event(
+        &res,
+        "multisig.config",
+        "set_amount_tiers",
+        &[("tiers", "none")]
+    ));
+    let resp: AmountTiersResp = multisig
+        .query(&QueryMsg::GetAmountTiers {
+            denom: DENOM.to_string(),
+        })
+        .unwrap();
+    assert!(resp.tiers.is_empty());
+}
+
+#[test]
+fn test_amount_tiers_invalid() {
+    let mut multisig = MultisigApp::new();
+    let invalid = ContractError::InvalidAmountTiers {
+        denom: DENOM.to_string(),
+        owners: 3,
+    };
+
+    for tiers in [
+        // Descending bounds
+        vec![tier(Some(100), 2), tier(Some(50), 3), tier(None, 3)],
+        // No unbounded tier
+        vec![tier(Some(100), 2), tier(Some(500), 3)],
+        // More signatures than owners
+        vec![tier(Some(100), 2), tier(None, 4)],
+        vec![tier(None, 0)],
+    ] {
+        let err = multisig
+            .approve(TxMsg::TxSelf(SelfTx::SetAmountTiers {
+                denom: DENOM.to_string(),
+                tiers,
+            }))
+            .unwrap_err();
+        assert_eq!(invalid, err);
+    }
+}
+
+#[test]
+fn test_contacts() {
+    let mut multisig = MultisigApp::new();
+
+    multisig
+        .approve(TxMsg::TxSelf(SelfTx::SetContact {
+            addr: addr("recipient"),
+            label: "Payroll".to_string(),
+            tags: vec!["team".to_string()],
+        }))
+        .unwrap();
+
+    let contact = Contact {
+        addr: addr("recipient"),
+        label: "Payroll".to_string(),
+        tags: vec!["team".to_string()],
+    };
+    let resp: Contact = multisig
+        .query(&QueryMsg::GetContact {
+            addr: addr("recipient"),
+        })
+        .unwrap();
+    assert_eq!(contact, resp);
+    let resp: ListContactsResp = multisig
+        .query(&QueryMsg::ListContacts {
+            start_after: None,
+            limit: None,
+        })
+        .unwrap();
+    assert_eq!(vec![contact.clone()], resp.contacts);
+
+    // Proposals show the label of known recipients and flag the others
+    let msg = multisig::msg::ExecuteMsg::CreateTransaction {
+        tx_msg: bank_tx("recipient", 5),
+        auto_execute: None,
+    };
+    let res = multisig.execute(OWNERS[0], &msg).unwrap();
+    assert!(has_event(
+        &res,
+        "multisig.tx",
+        "propose",
+        &[("recipient_label", "Payroll")]
+    ));
+    let msg = multisig::msg::ExecuteMsg::CreateTransaction {
+        tx_msg: bank_tx("stranger", 5),
+        auto_execute: None,
+    };
+    let res = multisig.execute(OWNERS[0], &msg).unwrap();
+    assert!(has_event(
+        &res,
+        "multisig.tx",
+        "propose",
+        &[("unknown_recipient", "true")]
+    ));
+    let pending: ListPendingResp = multisig.query(&QueryMsg::ListPending {}).unwrap();
+    assert_eq!(Some(contact), pending.recipients[0].contact);
+    assert_eq!(None, pending.recipients[1].contact);
+
+    multisig
+        .approve(TxMsg::TxSelf(SelfTx::RemoveContact {
+            addr: addr("recipient"),
+        }))
+        .unwrap();
+    assert!(multisig
+        .query::<Contact>(&QueryMsg::GetContact {
+            addr: addr("recipient"),
+        })
+        .is_err());
+
+    // A contact without tags reports them as none
+    let res = multisig
+        .approve(TxMsg::TxSelf(SelfTx::SetContact {
+            addr: addr("recipient"),
+            label: "Payroll".to_string(),
+            tags: vec![],
+        }))
+        .unwrap();
+    assert!(has_event(
+        &res,
+        "multisig.contact",
+        "set",
+        &[("tags", "none")]
+    ));
+}
+
+#[test]
+fn test_contacts_invalid() {
+    let mut multisig = MultisigApp::new();
+
+    let err = multisig
+        .approve(TxMsg::TxSelf(SelfTx::SetContact {
+            addr: addr("recipient"),
+            label: " ".to_string(),
+            tags: vec![],
+        }))
+        .unwrap_err();
+    assert_eq!(ContractError::EmptyContactLabel, err);
+
+    let err = multisig
+        .approve(TxMsg::TxSelf(SelfTx::RemoveContact {
+            addr: addr("recipient"),
+        }))
+        .unwrap_err();
+    assert_eq!(
+        ContractError::NonExistentContact("recipient".to_string()),
+        err
+    );
+}
+
+#[test]
+fn test_allowlist() {
+    let mut msg = instantiate_msg();
+    msg.allowlist = Some(vec![addr("recipient")]);
+    let mut multisig = MultisigApp::with_msg(msg);
+
+    multisig.approve(bank_tx("recipient", 10)).unwrap();
+    assert_eq!(
+        ContractError::RecipientNotAllowed("other".to_string()),
+        multisig.approve(bank_tx("other", 10)).unwrap_err()
+    );
+
+    let res = multisig
+        .approve(TxMsg::TxSelf(SelfTx::AllowRecipients {
+            addrs: vec![addr("other"), addr("zed")],
+        }))
+        .unwrap();
+    assert!(has_event(
+        &res,
+        "multisig.allowlist",
+        "allow",
+        &[("addrs", "other,zed")]
+    ));
+    // The rejected transaction is still pending
+    let tx_id = multisig.pending()[0].id;
+    multisig.execute_tx(OWNERS[0], tx_id).unwrap();
+    assert_eq!(10, multisig.balance("other"));
+
+    let resp: ListAllowlistResp = multisig
+        .query(&QueryMsg::ListAllowlist {
+            start_after: Some(addr("other")),
+            limit: None,
+        })
+        .unwrap();
+    assert!(resp.enabled);
+    assert_eq!(vec![addr("recipient"), addr("zed")], resp.addrs);
+
+    multisig
+        .approve(TxMsg::TxSelf(SelfTx::DisallowRecipients {
+            addrs: vec![addr("other")],
+        }))
+        .unwrap();
+    assert_eq!(
+        ContractError::RecipientNotAllowed("other".to_string()),
+        multisig.approve(bank_tx("other", 10)).unwrap_err()
+    );
+
+    multisig
+        .approve(TxMsg::TxSelf(SelfTx::SetAllowlistMode { enabled: false }))
+        .unwrap();
+    let config: ConfigResp = multisig.query(&QueryMsg::GetConfig {}).unwrap();
+    assert!(!config.allowlist_mode);
+    multisig.approve(bank_tx("nobody", 10)).unwrap();
+    assert_eq!(10, multisig.balance("nobody"));
+}
@@ -0,0 +1,438 @@
+use cosmwasm_std::{
+    coins, BankMsg, Binary, Coin, CosmosMsg, IbcMsg, IbcTimeout, StakingMsg, Timestamp, Uint128,
+    WasmMsg,
+};
+use cw_utils::{Expiration, ThresholdResponse};
+use multisig::msg::{
+    ExecuteMsg, ListProposalsResp, ListVotersResp, ListVotesResp, ProposalResp, ProposalStatus,
+    QueryMsg, VoteResp, VoterResp,
+};
+use multisig::state::{RateLimit, SelfTx, TxMsg, TxStatus, Vote};
+use multisig::ContractError;
+
+mod common;
+use common::{addr, bank_tx, has_event, MultisigApp, DENOM, OWNERS};
+
+fn send(to: &str, amount: u128) -> CosmosMsg {
+    BankMsg::Send {
+        to_address: to.to_string(),
+        amount: coins(amount, DENOM),
+    }
+    .into()
+}
+
+fn propose(
+    multisig: &mut MultisigApp,
+    msgs: Vec<CosmosMsg>,
+    latest: Option<Expiration>,
+) -> Result<u64, ContractError> {
+    let msg = ExecuteMsg::Propose {
+        title: "Payroll".to_string(),
+        description: "Monthly payroll".to_string(),
+        msgs,
+        latest,
+    };
+    multisig.execute(OWNERS[0], &msg)?;
+    Ok(multisig.last_tx_id() as u64)
+}
+
+fn vote(
+    multisig: &mut MultisigApp,
+    voter: &str,
+    proposal_id: u64,
+    vote: Vote,
+) -> Result<cw_multi_test::AppResponse, ContractError> {
+    multisig.execute(voter, &ExecuteMsg::Vote { proposal_id, vote })
+}
+
+/// Proposes `msgs`, votes them through and executes them
+fn execute_proposal(
+    multisig: &mut MultisigApp,
+    msgs: Vec<CosmosMsg>,
+) -> Result<cw_multi_test::AppResponse, ContractError> {
+    let proposal_id = propose(multisig, msgs, None)?;
+    vote(multisig, OWNERS[1], proposal_id, Vote::Yes)?;
+    multisig.execute(OWNERS[1], &ExecuteMsg::Execute { proposal_id })
+}
+
+fn ibc_transfer(to_address: &str, amount: u128) -> CosmosMsg {
+    IbcMsg::Transfer {
+        channel_id: "channel-0".to_string(),
+        to_address: to_address.to_string(),
+        amount: Coin::new(amount, DENOM),
+        timeout: IbcTimeout::with_timestamp(Timestamp::from_seconds(1_000_000)),
+    }
+    .into()
+}
+
+fn instantiate(amount: u128) -> CosmosMsg {
+    WasmMsg::Instantiate {
+        admin: None,
+        code_id: 1,
+        msg: Binary::default(),
+        funds: coins(amount, DENOM),
+        label: "vault".to_string(),
+    }
+    .into()
+}
+
+fn stargate() -> CosmosMsg {
+    CosmosMsg::Stargate {
+        type_url: "/cosmos.bank.v1beta1.MsgSend".to_string(),
+        value: Binary::default(),
+    }
+}
+
+fn proposal(multisig: &MultisigApp, proposal_id: u64) -> ProposalResp {
+    multisig.query(&QueryMsg::Proposal { proposal_id }).unwrap()
+}
+
+#[test]
+fn test_proposal() {
+    let mut multisig = MultisigApp::new();
+    let id = propose(
+        &mut multisig,
+        vec![send("recipient1", 10), send("recipient2", 5)],
+        None,
+    )
+    .unwrap();
+
+    let resp = proposal(&multisig, id);
+    assert_eq!("Payroll", resp.title);
+    assert_eq!(addr(OWNERS[0]), resp.proposer);
+    assert_eq!(2, resp.msgs.len());
+    assert_eq!(ProposalStatus::Open, resp.status);
+    assert_eq!(
+        ContractError::NotEnoughSignatures {
+            quorum: 2,
+            num_signed: 1
+        },
+        multisig
+            .execute(OWNERS[1], &ExecuteMsg::Execute { proposal_id: id })
+            .unwrap_err()
+    );
+
+    vote(&mut multisig, OWNERS[1], id, Vote::Yes).unwrap();
+    assert_eq!(ProposalStatus::Passed, proposal(&multisig, id).status);
+    assert_eq!(
+        ContractError::AlreadyVoted(id as u128),
+        vote(&mut multisig, OWNERS[1], id, Vote::No).unwrap_err()
+    );
+
+    multisig
+        .execute(OWNERS[2], &ExecuteMsg::Execute { proposal_id: id })
+        .unwrap();
+    assert_eq!(10, multisig.balance("recipient1"));
+    assert_eq!(5, multisig.balance("recipient2"));
+    assert_eq!(ProposalStatus::Executed, proposal(&multisig, id).status);
+}
+
+#[test]
+fn test_votes_and_voters() {
+    let mut multisig = MultisigApp::new();
+    let id = propose(&mut multisig, vec![send("recipient", 10)], None).unwrap();
+    let res = vote(&mut multisig, OWNERS[1], id, Vote::Abstain).unwrap();
+    assert!(has_event(
+        &res,
+        "multisig.tx",
+        "vote",
+        &[("ballot", "abstain")]
+    ));
+
+    let votes: ListVotesResp = multisig
+        .query(&QueryMsg::ListVotes {
+            proposal_id: id,
+            start_after: None,
+            limit: None,
+        })
+        .unwrap();
+    assert_eq!(2, votes.votes.len());
+    let votes: ListVotesResp = multisig
+        .query(&QueryMsg::ListVotes {
+            proposal_id: id,
+            start_after: Some(OWNERS[0].to_string()),
+            limit: None,
+        })
+        .unwrap();
+    assert_eq!(Vote::Abstain, votes.votes[0].vote);
+
+    let resp: VoteResp = multisig
+        .query(&QueryMsg::Vote {
+            proposal_id: id,
+            voter: OWNERS[0].to_string(),
+        })
+        .unwrap();
+    assert_eq!(Vote::Yes, resp.vote.unwrap().vote);
+    let resp: VoteResp = multisig
+        .query(&QueryMsg::Vote {
+            proposal_id: id,
+            voter: OWNERS[2].to_string(),
+        })
+        .unwrap();
+    assert_eq!(None, resp.vote);
+
+    let threshold: ThresholdResponse = multisig.query(&QueryMsg::Threshold {}).unwrap();
+    assert_eq!(
+        ThresholdResponse::AbsoluteCount {
+            weight: 2,
+            total_weight: 3
+        },
+        threshold
+    );
+    let voter: VoterResp = multisig
+        .query(&QueryMsg::Voter {
+            address: OWNERS[1].to_string(),
+        })
+        .unwrap();
+    assert_eq!(Some(1), voter.weight);
+    let voter: VoterResp = multisig
+        .query(&QueryMsg::Voter {
+            address: "stranger".to_string(),
+        })
+        .unwrap();
+    assert_eq!(None, voter.weight);
+    let voters: ListVotersResp = multisig
+        .query(&QueryMsg::ListVoters {
+            start_after: Some(OWNERS[0].to_string()),
+            limit: None,
+        })
+        .unwrap();
+    assert_eq!(2, voters.voters.len());
+}
+
+#[test]
+fn test_list_proposals() {
+    let mut multisig = MultisigApp::new();
+    propose(&mut multisig, vec![send("recipient", 10)], None).unwrap();
+    // Transactions are proposals too
+    multisig
+        .propose(OWNERS[0], bank_tx("recipient", 5))
+        .unwrap();
+
+    let resp: ListProposalsResp = multisig
+        .query(&QueryMsg::ReverseProposals {
+            start_before: None,
+            limit: None,
+        })
+        .unwrap();
+    let ids: Vec<u64> = resp.proposals.iter().map(|proposal| proposal.id).collect();
+    assert_eq!(vec![2, 1], ids);
+    assert_eq!("bank", resp.proposals[0].title);
+
+    let resp: ListProposalsResp = multisig
+        .query(&QueryMsg::ListProposals {
+            start_after: Some(1),
+            limit: None,
+        })
+        .unwrap();
+    assert_eq!(1, resp.proposals.len());
+}
+
+#[test]
+fn test_close() {
+    let mut multisig = MultisigApp::new();
+    let id = propose(&mut multisig, vec![send("recipient", 10)], None).unwrap();
+
+    assert_eq!(
+        ContractError::NotClosable(id as u128),
+        multisig
+            .execute("stranger", &ExecuteMsg::Close { proposal_id: id })
+            .unwrap_err()
+    );
+
+    // With two owners against it the proposal can't reach the quorum
+    vote(&mut multisig, OWNERS[1], id, Vote::No).unwrap();
+    assert_eq!(ProposalStatus::Open, proposal(&multisig, id).status);
+    vote(&mut multisig, OWNERS[2], id, Vote::Veto).unwrap();
+    assert_eq!(ProposalStatus::Rejected, proposal(&multisig, id).status);
+
+    let res = multisig
+        .execute("stranger", &ExecuteMsg::Close { proposal_id: id })
+        .unwrap();
+    assert!(has_event(&res, "multisig.tx", "close", &[]));
+    assert_eq!(TxStatus::Rejected, multisig.completed()[0].status);
+    assert_eq!(ProposalStatus::Rejected, proposal(&multisig, id).status);
+}
+
+#[test]
+fn test_expiration() {
+    let mut multisig = MultisigApp::new();
+    let height = multisig.app.block_info().height;
+
+    assert_eq!(
+        ContractError::WrongExpiration,
+        propose(
+            &mut multisig,
+            vec![send("recipient", 10)],
+            Some(Expiration::AtHeight(height))
+        )
+        .unwrap_err()
+    );
+
+    let id = propose(
+        &mut multisig,
+        vec![send("recipient", 10)],
+        Some(Expiration::AtHeight(height + 5)),
+    )
+    .unwrap();
+    multisig.app.update_block(|block| block.height += 10);
+
+    assert_eq!(
+        ContractError::Expired(id as u128),
+        vote(&mut multisig, OWNERS[1], id, Vote::Yes).unwrap_err()
+    );
+    assert_eq!(
+        ContractError::Expired(id as u128),
+        vote(&mut multisig, OWNERS[1], id, Vote::No).unwrap_err()
+    );
+    assert_eq!(ProposalStatus::Rejected, proposal(&multisig, id).status);
+    multisig
+        .execute(OWNERS[1], &ExecuteMsg::Close { proposal_id: id })
+        .unwrap();
+}
+
+#[test]
+fn test_proposal_messages() {
+    let mut multisig = MultisigApp::new();
+
+    let self_send = send(multisig.wallet.as_str(), 1);
+    let id = propose(&mut multisig, vec![self_send], None).unwrap();
+    vote(&mut multisig, OWNERS[1], id, Vote::Yes).unwrap();
+    assert_eq!(
+        ContractError::SelfCall,
+        multisig
+            .execute(OWNERS[1], &ExecuteMsg::Execute { proposal_id: id })
+            .unwrap_err()
+    );
+
+    // The messages are sent together, one failing reverts the others and the failure is
+    // recorded with the proposal still open to another execution
+    let id = propose(
+        &mut multisig,
+        vec![send("recipient", 1), send("recipient", 100_000)],
+        None,
+    )
+    .unwrap();
+    vote(&mut multisig, OWNERS[1], id, Vote::Yes).unwrap();
+    let res = multisig
+        .execute(OWNERS[1], &ExecuteMsg::Execute { proposal_id: id })
+        .unwrap();
+    assert!(has_event(&res, "multisig.tx", "execution_failed", &[]));
+    assert_eq!(0, multisig.balance("recipient"));
+    assert_eq!(ProposalStatus::Passed, proposal(&multisig, id).status);
+
+    // Only the wallet sends the messages of its proposals
+    assert_eq!(
+        ContractError::Unauthorized {},
+        multisig
+            .execute(
+                OWNERS[0],
+                &ExecuteMsg::SendMessages {
+                    msgs: vec![send("recipient", 1)]
+                }
+            )
+            .unwrap_err()
+    );
+}
+
+#[test]
+fn test_proposal_allowlist() {
+    let mut multisig = MultisigApp::new();
+    multisig
+        .approve(TxMsg::TxSelf(SelfTx::AllowRecipients {
+            addrs: vec![addr("recipient"), addr("token")],
+        }))
+        .unwrap();
+    multisig
+        .approve(TxMsg::TxSelf(SelfTx::SetAllowlistMode { enabled: true }))
+        .unwrap();
+
+    assert_eq!(
+        ContractError::RecipientNotAllowed("cosmos1stranger".to_string()),
+        execute_proposal(&mut multisig, vec![ibc_transfer("cosmos1stranger", 1)]).unwrap_err()
+    );
+
+    // cw20 tokens can only go to allowlisted recipients either
+    let cw20_transfer = WasmMsg::Execute {
+        contract_addr: addr("token").to_string(),
+        msg: Binary::from(br#"{"transfer":{"recipient":"stranger","amount":"100"}}"#),
+        funds: vec![],
+    };
+    assert_eq!(
+        ContractError::RecipientNotAllowed("stranger".to_string()),
+        execute_proposal(&mut multisig, vec![cw20_transfer.into()]).unwrap_err()
+    );
+
+    for msg in [instantiate(1), stargate()] {
+        assert_eq!(
+            ContractError::UnverifiableMsg,
+            execute_proposal(&mut multisig, vec![msg]).unwrap_err()
+        );
+    }
+}
+
+#[test]
+fn test_proposal_rate_limit() {
+    let mut multisig = MultisigApp::new();
+    multisig
+        .approve(TxMsg::TxSelf(SelfTx::SetRateLimit {
+            denom: DENOM.to_string(),
+            limit: Some(RateLimit {
+                amount: Uint128::new(10),
+                window: 1000,
+            }),
+        }))
+        .unwrap();
+
+    // Funds of instantiations and IBC transfers count with the others
+    for msgs in [
+        vec![send("recipient", 6), instantiate(6)],
+        vec![send("recipient", 6), ibc_transfer("cosmos1recipient", 6)],
+    ] {
+        assert_eq!(
+            ContractError::RateLimitExceeded {
+                denom: DENOM.to_string(),
+                available: Uint128::new(10),
+                required: Uint128::new(12)
+            },
+            execute_proposal(&mut multisig, msgs).unwrap_err()
+        );
+    }
+
+    // cw20 tokens are limited by their contract address
+    let token = addr("token").to_string();
+    multisig
+        .approve(TxMsg::TxSelf(SelfTx::SetRateLimit {
+            denom: token.clone(),
+            limit: Some(RateLimit {
+                amount: Uint128::new(10),
+                window: 1000,
+            }),
+        }))
+        .unwrap();
+    let cw20_transfer: CosmosMsg = WasmMsg::Execute {
+        contract_addr: token.clone(),
+        msg: Binary::from(br#"{"transfer":{"recipient":"recipient","amount":"6"}}"#),
+        funds: vec![],
+    }
+    .into();
+    assert_eq!(
+        ContractError::RateLimitExceeded {
+            denom: token,
+            available: Uint128::new(10),
+            required: Uint128::new(12)
+        },
+        execute_proposal(&mut multisig, vec![cw20_transfer.clone(), cw20_transfer]).unwrap_err()
+    );
+
+    let delegate = StakingMsg::Delegate {
+        validator: "validator".to_string(),
+        amount: Coin::new(100, DENOM),
+    };
+    for msg in [stargate(), delegate.into()] {
+        assert_eq!(
+            ContractError::UnverifiableMsg,
+            execute_proposal(&mut multisig, vec![msg]).unwrap_err()
+        );
+    }
+}
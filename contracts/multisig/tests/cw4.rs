@@ -0,0 +1,347 @@
+use cosmwasm_std::{to_json_binary, Addr, WasmMsg};
+use cw_multi_test::{AppResponse, Executor};
+use multisig::msg::{
+    ConfigResp, ExecuteMsg, HooksResp, InstantiateMsg, ListMembersResp, Member, MemberDiff,
+    MemberResp, QueryMsg, TotalWeightResp,
+};
+use multisig::state::{GuardianSet, SelfTx, TxMsg};
+use multisig::ContractError;
+
+mod common;
+use common::{addr, bank_tx, has_event, instantiate_msg, mock_group, MultisigApp, OWNERS};
+
+fn member(addr: &str, weight: u64) -> Member {
+    Member {
+        addr: addr.to_string(),
+        weight,
+    }
+}
+
+fn group_msg(group: &Addr) -> InstantiateMsg {
+    InstantiateMsg {
+        owners: vec![],
+        quorum: 3,
+        group: Some(group.clone()),
+        ..instantiate_msg()
+    }
+}
+
+/// Instantiates a cw4 group with `admin` and members aaa (2), bbb (1), ccc (1) and zzz (0)
+fn setup_group(multisig: &mut MultisigApp, admin: &str) -> Addr {
+    let code_id = multisig.store_code(mock_group::contract());
+    multisig.instantiate_other(
+        code_id,
+        &mock_group::InstantiateMsg {
+            admin: admin.to_string(),
+            members: vec![
+                member("aaa", 2),
+                member("bbb", 1),
+                member("ccc", 1),
+                member("zzz", 0),
+            ],
+        },
+    )
+}
+
+/// App whose wallet is a 3 of 4 wallet backed by a group it administers
+fn group_wallet() -> (MultisigApp, Addr) {
+    let mut multisig = MultisigApp::new();
+    // Contracts get sequential addresses: the group is contract1, its wallet contract2
+    let wallet = addr("contract2");
+    let group = setup_group(&mut multisig, wallet.as_str());
+    multisig.wallet = multisig.instantiate(&group_msg(&group)).unwrap();
+    assert_eq!(wallet, multisig.wallet);
+    (multisig, group)
+}
+
+fn update_members(
+    multisig: &mut MultisigApp,
+    group: &Addr,
+    remove: &[&str],
+    add: Vec<Member>,
+) -> AppResponse {
+    let msg = mock_group::ExecuteMsg::UpdateMembers {
+        remove: remove.iter().map(|addr| addr.to_string()).collect(),
+        add,
+    };
+    let wallet = multisig.wallet.clone();
+    multisig
+        .app
+        .execute_contract(wallet, group.clone(), &msg, &[])
+        .unwrap()
+}
+
+#[test]
+fn test_group_wallet() {
+    let (mut multisig, group) = group_wallet();
+
+    let config: ConfigResp = multisig.query(&QueryMsg::GetConfig {}).unwrap();
+    assert_eq!(Some(group), config.group);
+    let resp: MemberResp = multisig
+        .query(&QueryMsg::Member {
+            addr: "aaa".to_string(),
+            at_height: None,
+        })
+        .unwrap();
+    assert_eq!(Some(2), resp.weight);
+    let resp: ListMembersResp = multisig
+        .query(&QueryMsg::ListMembers {
+            start_after: None,
+            limit: None,
+        })
+        .unwrap();
+    assert_eq!(4, resp.members.len());
+    let resp: TotalWeightResp = multisig
+        .query(&QueryMsg::TotalWeight { at_height: None })
+        .unwrap();
+    assert_eq!(4, resp.weight);
+
+    // The quorum is checked against the group's total weight
+    assert_eq!(
+        ContractError::WrongQuorum {
+            quorum: 5,
+            owners: 4
+        },
+        multisig
+            .instantiate(&InstantiateMsg {
+                quorum: 5,
+                ..group_msg(&config.group.unwrap())
+            })
+            .unwrap_err()
+    );
+}
+
+#[test]
+fn test_weighted_signatures() {
+    let (mut multisig, _) = group_wallet();
+
+    assert_eq!(
+        ContractError::Unauthorized {},
+        multisig
+            .propose("zzz", bank_tx("recipient", 10))
+            .unwrap_err()
+    );
+    assert_eq!(
+        ContractError::Unauthorized {},
+        multisig
+            .propose(OWNERS[0], bank_tx("recipient", 10))
+            .unwrap_err()
+    );
+
+    let tx_id = multisig.propose("aaa", bank_tx("recipient", 10)).unwrap();
+    assert_eq!(2, multisig.tx(tx_id).num_confirmations);
+    assert_eq!(
+        ContractError::NotEnoughSignatures {
+            quorum: 3,
+            num_signed: 2
+        },
+        multisig.execute_tx("aaa", tx_id).unwrap_err()
+    );
+
+    multisig.sign("bbb", tx_id).unwrap();
+    assert_eq!(3, multisig.tx(tx_id).num_confirmations);
+    multisig.execute_tx("ccc", tx_id).unwrap();
+    assert_eq!(10, multisig.balance("recipient"));
+}
+
+#[test]
+fn test_add_hook_failed() {
+    let mut multisig = MultisigApp::new();
+    let group = setup_group(&mut multisig, "admin");
+
+    // The wallet isn't the group's admin, so it can't add its hook
+    let msg = WasmMsg::Instantiate {
+        admin: None,
+        code_id: multisig.code_id,
+        msg: to_json_binary(&group_msg(&group)).unwrap(),
+        funds: vec![],
+        label: "Multisig".to_string(),
+    };
+    let res = multisig.app.execute(addr("creator"), msg.into()).unwrap();
+    assert!(has_event(
+        &res,
+        "multisig.config",
+        "add_hook_failed",
+        &[("group", group.as_str())]
+    ));
+}
+
+#[test]
+fn test_weight_too_large() {
+    let (mut multisig, group) = group_wallet();
+    let weight = u64::from(u32::MAX) + 1;
+    update_members(&mut multisig, &group, &[], vec![member("whale", weight)]);
+
+    assert_eq!(
+        ContractError::WeightTooLarge(weight),
+        multisig
+            .propose("whale", bank_tx("recipient", 1))
+            .unwrap_err()
+    );
+    let tx_id = multisig.propose("aaa", bank_tx("recipient", 1)).unwrap();
+    assert_eq!(
+        ContractError::WeightTooLarge(weight),
+        multisig.sign("whale", tx_id).unwrap_err()
+    );
+
+    // The total weight no longer fits either
+    assert_eq!(
+        ContractError::WeightTooLarge(weight + 4),
+        multisig.instantiate(&group_msg(&group)).unwrap_err()
+    );
+}
+
+#[test]
+fn test_group_managed() {
+    let (mut multisig, group) = group_wallet();
+    let err = ContractError::GroupManaged(group.to_string());
+
+    let tx_id = multisig
+        .propose(
+            "aaa",
+            TxMsg::TxSelf(SelfTx::AddOwner {
+                owner: addr("ddd"),
+                quorum: None,
+            }),
+        )
+        .unwrap();
+    multisig.sign("bbb", tx_id).unwrap();
+    assert_eq!(err, multisig.execute_tx("aaa", tx_id).unwrap_err());
+
+    assert_eq!(
+        err,
+        multisig
+            .instantiate(&InstantiateMsg {
+                owners: vec![addr("aaa")],
+                ..group_msg(&group)
+            })
+            .unwrap_err()
+    );
+
+    let mut msg = group_msg(&group);
+    msg.guardians = Some(GuardianSet {
+        guardians: vec![addr("guardian1")],
+        threshold: 1,
+        recovery_delay: 100,
+    });
+    let wallet = multisig.instantiate(&msg).unwrap();
+    multisig.wallet = wallet;
+    assert_eq!(
+        err,
+        multisig
+            .execute(
+                "guardian1",
+                &ExecuteMsg::ProposeRecovery {
+                    owners: vec![addr("ddd")],
+                    quorum: 1,
+                }
+            )
+            .unwrap_err()
+    );
+}
+
+#[test]
+fn test_member_changed() {
+    let (mut multisig, group) = group_wallet();
+    let tx_id = multisig.propose("aaa", bank_tx("recipient", 10)).unwrap();
+    multisig.sign("bbb", tx_id).unwrap();
+
+    // A removed member's vote is dropped, a weight change keeps it
+    let res = update_members(
+        &mut multisig,
+        &group,
+        &["bbb"],
+        vec![member("aaa", 1), member("ddd", 1)],
+    );
+    assert!(has_event(
+        &res,
+        "multisig.owner",
+        "remove",
+        &[("owner", "bbb"), ("invalidated", &tx_id.to_string())]
+    ));
+    assert!(has_event(
+        &res,
+        "multisig.owner",
+        "add",
+        &[("owner", "ddd")]
+    ));
+    assert_eq!(2, multisig.tx(tx_id).num_confirmations);
+
+    multisig.sign("ddd", tx_id).unwrap();
+    multisig.execute_tx("ddd", tx_id).unwrap();
+    assert_eq!(10, multisig.balance("recipient"));
+
+    // Only the group can call the hook
+    let msg = ExecuteMsg::MemberChangedHook {
+        diffs: vec![MemberDiff {
+            key: "ccc".to_string(),
+            old: Some(1),
+            new: None,
+        }],
+    };
+    assert_eq!(
+        ContractError::Unauthorized {},
+        multisig.execute("ccc", &msg).unwrap_err()
+    );
+}
+
+#[test]
+fn test_hooks() {
+    let mut multisig = MultisigApp::new();
+    let wallet = multisig.wallet.clone();
+    // A wallet backed by the first one is notified through its hook
+    let hooked = multisig
+        .instantiate(&InstantiateMsg {
+            owners: vec![],
+            group: Some(wallet.clone()),
+            ..instantiate_msg()
+        })
+        .unwrap();
+
+    multisig
+        .approve(TxMsg::TxSelf(SelfTx::AddHook {
+            hook: hooked.clone(),
+        }))
+        .unwrap();
+    let resp: HooksResp = multisig.query(&QueryMsg::Hooks {}).unwrap();
+    assert_eq!(vec![hooked.to_string()], resp.hooks);
+
+    let res = multisig
+        .approve(TxMsg::TxSelf(SelfTx::AddOwner {
+            owner: addr("owner4"),
+            quorum: None,
+        }))
+        .unwrap();
+    assert!(has_event(
+        &res,
+        "multisig.owner",
+        "add",
+        &[("owner", "owner4"), ("_contract_address", hooked.as_str())]
+    ));
+    let res = multisig
+        .approve(TxMsg::TxSelf(SelfTx::RemoveOwner {
+            owner: addr("owner4"),
+            quorum: None,
+        }))
+        .unwrap();
+    assert!(has_event(
+        &res,
+        "multisig.owner",
+        "remove",
+        &[("owner", "owner4"), ("_contract_address", hooked.as_str())]
+    ));
+
+    multisig
+        .approve(TxMsg::TxSelf(SelfTx::RemoveHook {
+            hook: hooked.clone(),
+        }))
+        .unwrap();
+    let resp: HooksResp = multisig.query(&QueryMsg::Hooks {}).unwrap();
+    assert!(resp.hooks.is_empty());
+    assert_eq!(
+        ContractError::NonExistentHook(hooked.to_string()),
+        multisig
+            .approve(TxMsg::TxSelf(SelfTx::RemoveHook { hook: hooked }))
+            .unwrap_err()
+    );
+}
@@ -0,0 +1,141 @@
+use cosmwasm_std::{Decimal, VoteOption, WeightedVoteOption};
+use cw_multi_test::GovAcceptingModule;
+use multisig::state::{SelfTx, TxCategory, TxMsg, TxStatus};
+use multisig::ContractError;
+
+mod common;
+use common::{has_event, MultisigApp, OWNERS};
+
+#[test]
+fn test_gov_vote() {
+    let mut multisig = MultisigApp::with_gov(GovAcceptingModule::new());
+
+    let tx_msg = TxMsg::GovVote {
+        proposal_id: 7,
+        vote: VoteOption::Yes,
+    };
+    let res = multisig.approve(tx_msg.clone()).unwrap();
+    assert!(has_event(
+        &res,
+        "multisig.tx",
+        "executed",
+        &[
+            ("proposal_id", "7"),
+            ("vote", "yes"),
+            ("msg_type", "gov_vote")
+        ]
+    ));
+
+    let tx = multisig.completed().pop().unwrap();
+    assert_eq!(multisig.last_tx_id(), tx.id);
+    assert_eq!(tx_msg, tx.tx_msg);
+    assert_eq!(TxStatus::Executed, tx.status);
+    assert!(multisig.pending().is_empty());
+}
+
+#[test]
+fn test_gov_vote_weighted() {
+    let mut multisig = MultisigApp::with_gov(GovAcceptingModule::new());
+
+    let tx_msg = TxMsg::GovVoteWeighted {
+        proposal_id: 3,
+        options: vec![
+            WeightedVoteOption {
+                option: VoteOption::Yes,
+                weight: Decimal::percent(70),
+            },
+            WeightedVoteOption {
+                option: VoteOption::Abstain,
+                weight: Decimal::percent(30),
+            },
+        ],
+    };
+    let res = multisig.approve(tx_msg).unwrap();
+
+    assert!(has_event(
+        &res,
+        "multisig.tx",
+        "executed",
+        &[("proposal_id", "3"), ("vote", "yes:0.7,abstain:0.3")]
+    ));
+    let tx = multisig.completed().pop().unwrap();
+    assert_eq!(TxStatus::Executed, tx.status);
+}
+
+#[test]
+fn test_gov_vote_rejected_by_chain() {
+    // multi-test's default governance module fails every message
+    let mut multisig = MultisigApp::new();
+
+    let tx_msg = TxMsg::GovVote {
+        proposal_id: 1,
+        vote: VoteOption::No,
+    };
+    multisig.approve(tx_msg).unwrap();
+
+    // The vote failed, the transaction stays pending so it can be retried
+    let tx = multisig.pending().pop().unwrap();
+    assert_eq!(multisig.last_tx_id(), tx.id);
+    assert!(matches!(tx.status, TxStatus::ExecutionFailed { .. }));
+    assert!(multisig.completed().is_empty());
+}
+
+#[test]
+fn test_chain_vote_threshold() {
+    let mut multisig = MultisigApp::with_gov(GovAcceptingModule::new());
+
+    multisig
+        .approve(TxMsg::TxSelf(SelfTx::SetThreshold {
+            category: TxCategory::ChainVote,
+            threshold: Some(3),
+        }))
+        .unwrap();
+
+    let tx_msg = TxMsg::GovVote {
+        proposal_id: 1,
+        vote: VoteOption::NoWithVeto,
+    };
+    assert_eq!(TxCategory::ChainVote, tx_msg.category());
+    assert_eq!(
+        ContractError::NotEnoughSignatures {
+            quorum: 3,
+            num_signed: 2
+        },
+        multisig.approve(tx_msg).unwrap_err()
+    );
+
+    let tx_id = multisig.last_tx_id();
+    multisig.sign(OWNERS[2], tx_id).unwrap();
+    multisig.execute_tx(OWNERS[0], tx_id).unwrap();
+
+    let tx = multisig.completed().pop().unwrap();
+    assert_eq!(tx_id, tx.id);
+    assert_eq!(TxStatus::Executed, tx.status);
+}
+
+#[test]
+fn test_gov_vote_display() {
+    let tx_msg = TxMsg::GovVote {
+        proposal_id: 5,
+        vote: VoteOption::Abstain,
+    };
+    assert_eq!("GovVote {proposal: 5, vote: abstain}", tx_msg.to_string());
+
+    let tx_msg = TxMsg::GovVoteWeighted {
+        proposal_id: 5,
+        options: vec![
+            WeightedVoteOption {
+                option: VoteOption::No,
+                weight: Decimal::percent(50),
+            },
+            WeightedVoteOption {
+                option: VoteOption::NoWithVeto,
+                weight: Decimal::percent(50),
+            },
+        ],
+    };
+    assert_eq!(
+        "GovVoteWeighted {proposal: 5, options: no:0.5,no_with_veto:0.5}",
+        tx_msg.to_string()
+    );
+}
@@ -0,0 +1,214 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{
+    to_json_binary, Addr, Binary, Deps, DepsMut, Empty, Env, MessageInfo, Response, StdError,
+    StdResult,
+};
+use cw_multi_test::{Contract, ContractWrapper, Executor};
+use cw_storage_plus::Item;
+use multisig::msg::{ConfigResp, GuardCheckResp, QueryMsg};
+use multisig::state::{SelfTx, Transaction, TxMsg};
+use multisig::ContractError;
+
+mod common;
+use common::{addr, bank_tx, MultisigApp, OWNERS, WALLET_FUNDS};
+
+/// Guard rejecting transfers to `blocked` and every transaction once `broken`
+mod mock_guard {
+    use super::*;
+
+    pub const BROKEN: Item<bool> = Item::new("broken");
+    pub const NOTIFICATIONS: Item<Vec<(u128, bool)>> = Item::new("notifications");
+
+    #[cw_serde]
+    pub enum ExecuteMsg {
+        AfterExecution { tx: Box<Transaction>, success: bool },
+        Break {},
+    }
+
+    #[cw_serde]
+    pub enum QueryMsg {
+        CheckTransaction {
+            tx: Box<Transaction>,
+            signers: Vec<Addr>,
+            executor: Addr,
+        },
+        Notifications {},
+    }
+
+    pub fn instantiate(
+        deps: DepsMut,
+        _env: Env,
+        _info: MessageInfo,
+        _msg: Empty,
+    ) -> StdResult<Response> {
+        BROKEN.save(deps.storage, &false)?;
+        NOTIFICATIONS.save(deps.storage, &vec![])?;
+        Ok(Response::new())
+    }
+
+    pub fn execute(
+        deps: DepsMut,
+        _env: Env,
+        _info: MessageInfo,
+        msg: ExecuteMsg,
+    ) -> StdResult<Response> {
+        match msg {
+            ExecuteMsg::AfterExecution { tx, success } => {
+                NOTIFICATIONS.update(deps.storage, |mut notifications| -> StdResult<_> {
+                    notifications.push((tx.id, success));
+                    Ok(notifications)
+                })?;
+            }
+            ExecuteMsg::Break {} => BROKEN.save(deps.storage, &true)?,
+        }
+        Ok(Response::new())
+    }
+
+    pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+        match msg {
+            QueryMsg::CheckTransaction { tx, signers, .. } => {
+                if BROKEN.load(deps.storage)? {
+                    return Err(StdError::generic_err("guard is broken"));
+                }
+
+                let resp = match tx.tx_msg {
+                    TxMsg::TxBank { to, .. } if to == addr("blocked") => GuardCheckResp {
+                        allowed: false,
+                        reason: Some("blocked recipient".to_string()),
+                    },
+                    _ if signers.len() < 2 => GuardCheckResp {
+                        allowed: false,
+                        reason: Some("not enough signers".to_string()),
+                    },
+                    _ => GuardCheckResp {
+                        allowed: true,
+                        reason: None,
+                    },
+                };
+                to_json_binary(&resp)
+            }
+            QueryMsg::Notifications {} => to_json_binary(&NOTIFICATIONS.load(deps.storage)?),
+        }
+    }
+
+    pub fn contract() -> Box<dyn Contract<Empty>> {
+        Box::new(ContractWrapper::new(execute, instantiate, query))
+    }
+}
+
+/// Wallet of the default app and a guard it isn't using yet
+fn instantiate_contracts() -> (MultisigApp, Addr) {
+    let mut multisig = MultisigApp::new();
+    let code_id = multisig.store_code(mock_guard::contract());
+    let guard = multisig.instantiate_other(code_id, &Empty {});
+    (multisig, guard)
+}
+
+fn set_guard(multisig: &mut MultisigApp, guard: Option<Addr>) {
+    multisig
+        .approve(TxMsg::TxSelf(SelfTx::SetGuard { guard }))
+        .unwrap();
+}
+
+fn notifications(multisig: &MultisigApp, guard: &Addr) -> Vec<(u128, bool)> {
+    multisig
+        .app
+        .wrap()
+        .query_wasm_smart(guard, &mock_guard::QueryMsg::Notifications {})
+        .unwrap()
+}
+
+#[test]
+fn test_set_guard() {
+    let (mut multisig, guard) = instantiate_contracts();
+
+    set_guard(&mut multisig, Some(guard.clone()));
+
+    let config: ConfigResp = multisig.query(&QueryMsg::GetConfig {}).unwrap();
+    assert_eq!(Some(guard.clone()), config.guard);
+    // Changing the guard isn't reported to it
+    assert!(notifications(&multisig, &guard).is_empty());
+}
+
+#[test]
+fn test_guard_allows_and_is_notified() {
+    let (mut multisig, guard) = instantiate_contracts();
+    set_guard(&mut multisig, Some(guard.clone()));
+
+    multisig.approve(bank_tx("recipient", 10)).unwrap();
+
+    assert_eq!(10, multisig.balance("recipient"));
+    assert_eq!(
+        vec![(multisig.last_tx_id(), true)],
+        notifications(&multisig, &guard)
+    );
+}
+
+#[test]
+fn test_guard_rejects() {
+    let (mut multisig, guard) = instantiate_contracts();
+    set_guard(&mut multisig, Some(guard.clone()));
+
+    assert_eq!(
+        ContractError::GuardRejected {
+            reason: "blocked recipient".to_string()
+        },
+        multisig.approve(bank_tx("blocked", 10)).unwrap_err()
+    );
+    assert!(notifications(&multisig, &guard).is_empty());
+}
+
+#[test]
+fn test_guard_receives_signers() {
+    let (mut multisig, guard) = instantiate_contracts();
+    set_guard(&mut multisig, Some(guard));
+
+    let tx_id = multisig
+        .propose(OWNERS[0], bank_tx("recipient", 10))
+        .unwrap();
+    // The wallet would execute it once the quorum is lowered, but the guard counts the signers
+    multisig
+        .approve(TxMsg::TxSelf(SelfTx::UpdateQuorum { quorum: 1 }))
+        .unwrap();
+
+    assert_eq!(
+        ContractError::GuardRejected {
+            reason: "not enough signers".to_string()
+        },
+        multisig.execute_tx(OWNERS[0], tx_id).unwrap_err()
+    );
+}
+
+#[test]
+fn test_guard_notified_of_failed_execution() {
+    let (mut multisig, guard) = instantiate_contracts();
+    set_guard(&mut multisig, Some(guard.clone()));
+
+    multisig
+        .approve(bank_tx("recipient", WALLET_FUNDS + 1))
+        .unwrap();
+
+    assert_eq!(
+        vec![(multisig.last_tx_id(), false)],
+        notifications(&multisig, &guard)
+    );
+}
+
+#[test]
+fn test_broken_guard_can_be_replaced() {
+    let (mut multisig, guard) = instantiate_contracts();
+    set_guard(&mut multisig, Some(guard.clone()));
+
+    multisig
+        .app
+        .execute_contract(addr("owner"), guard, &mock_guard::ExecuteMsg::Break {}, &[])
+        .unwrap();
+
+    assert!(matches!(
+        multisig.approve(bank_tx("recipient", 10)).unwrap_err(),
+        ContractError::Std(_)
+    ));
+
+    set_guard(&mut multisig, None);
+    multisig.approve(bank_tx("recipient", 10)).unwrap();
+}
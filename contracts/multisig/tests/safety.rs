@@ -0,0 +1,517 @@
+use cosmwasm_std::Uint128;
+use multisig::msg::{
+    ExecuteMsg, GuardiansResp, ListAdminsResp, ListSignedResp, PauseResp, ProposalResp, QueryMsg,
+    RateLimitResp, RecoveryResp, VoteResp,
+};
+use multisig::state::{GuardianSet, RateLimit, SelfTx, TxCategory, TxMsg, TxStatus};
+use multisig::ContractError;
+
+mod common;
+use common::{addr, bank_tx, has_event, instantiate_msg, MultisigApp, DENOM, OWNERS};
+
+const GUARDIANS: [&str; 3] = ["guardian1", "guardian2", "guardian3"];
+const RECOVERY_DELAY: u64 = 100;
+
+fn guardian_set() -> GuardianSet {
+    GuardianSet {
+        guardians: GUARDIANS.iter().map(|guardian| addr(guardian)).collect(),
+        threshold: 2,
+        recovery_delay: RECOVERY_DELAY,
+    }
+}
+
+fn with_guardians() -> MultisigApp {
+    let mut msg = instantiate_msg();
+    msg.guardians = Some(guardian_set());
+    MultisigApp::with_msg(msg)
+}
+
+fn propose_recovery(multisig: &mut MultisigApp, guardian: &str) -> Result<(), ContractError> {
+    let msg = ExecuteMsg::ProposeRecovery {
+        owners: vec![addr("new_owner1"), addr("new_owner2")],
+        quorum: 2,
+    };
+    multisig.execute(guardian, &msg).map(|_| ())
+}
+
+fn rate_limit(amount: u128, window: u64) -> Option<RateLimit> {
+    Some(RateLimit {
+        amount: Uint128::new(amount),
+        window,
+    })
+}
+
+fn get_rate_limit(multisig: &MultisigApp) -> RateLimitResp {
+    multisig
+        .query(&QueryMsg::GetRateLimit {
+            denom: DENOM.to_string(),
+        })
+        .unwrap()
+}
+
+#[test]
+fn test_pause() {
+    let mut msg = instantiate_msg();
+    msg.pause_duration = Some(1000);
+    let mut multisig = MultisigApp::with_msg(msg);
+    let tx_id = multisig
+        .propose(OWNERS[0], bank_tx("recipient", 5))
+        .unwrap();
+    multisig.sign(OWNERS[1], tx_id).unwrap();
+
+    assert_eq!(
+        ContractError::Unauthorized {},
+        multisig
+            .execute("stranger", &ExecuteMsg::Pause {})
+            .unwrap_err()
+    );
+    multisig.execute(OWNERS[2], &ExecuteMsg::Pause {}).unwrap();
+
+    let pause: PauseResp = multisig.query(&QueryMsg::GetPauseState {}).unwrap();
+    assert!(pause.paused);
+    assert_eq!(Some(addr(OWNERS[2])), pause.paused_by);
+    let until = pause.expires_at.unwrap();
+    assert_eq!(
+        ContractError::AlreadyPaused { until },
+        multisig
+            .execute(OWNERS[0], &ExecuteMsg::Pause {})
+            .unwrap_err()
+    );
+    assert_eq!(
+        ContractError::Paused { until },
+        multisig.execute_tx(OWNERS[0], tx_id).unwrap_err()
+    );
+    assert_eq!(
+        ContractError::Paused { until },
+        multisig
+            .propose(OWNERS[0], bank_tx("recipient", 5))
+            .unwrap_err()
+    );
+
+    // The pause expires on its own
+    multisig.advance(1000);
+    multisig.execute_tx(OWNERS[0], tx_id).unwrap();
+    assert_eq!(5, multisig.balance("recipient"));
+}
+
+#[test]
+fn test_pause_cooldown() {
+    let mut msg = instantiate_msg();
+    msg.pause_duration = Some(1000);
+    let mut multisig = MultisigApp::with_msg(msg);
+    multisig.execute(OWNERS[2], &ExecuteMsg::Pause {}).unwrap();
+    multisig.advance(1000);
+
+    // Another owner can't pause right after the pause expired
+    let pause: PauseResp = multisig.query(&QueryMsg::GetPauseState {}).unwrap();
+    assert!(!pause.paused);
+    let until = pause.cooldown_until.unwrap();
+    assert_eq!(
+        ContractError::PauseCooldown { until },
+        multisig
+            .execute(OWNERS[1], &ExecuteMsg::Pause {})
+            .unwrap_err()
+    );
+    // Which leaves the other owners time to remove the one that paused
+    multisig
+        .approve(TxMsg::TxSelf(SelfTx::RemoveOwner {
+            owner: addr(OWNERS[2]),
+            quorum: None,
+        }))
+        .unwrap();
+
+    multisig.advance(1000);
+    multisig.execute(OWNERS[1], &ExecuteMsg::Pause {}).unwrap();
+
+    // Lifting a pause starts the cooldown too
+    multisig.approve(TxMsg::TxSelf(SelfTx::Unpause {})).unwrap();
+    assert!(matches!(
+        multisig
+            .execute(OWNERS[0], &ExecuteMsg::Pause {})
+            .unwrap_err(),
+        ContractError::PauseCooldown { .. }
+    ));
+    multisig.advance(1000);
+    multisig.execute(OWNERS[0], &ExecuteMsg::Pause {}).unwrap();
+}
+
+#[test]
+fn test_unpause() {
+    let mut multisig = MultisigApp::new();
+    multisig.execute(OWNERS[0], &ExecuteMsg::Pause {}).unwrap();
+
+    let res = multisig.approve(TxMsg::TxSelf(SelfTx::Unpause {})).unwrap();
+
+    assert!(has_event(&res, "multisig.pause", "unpause", &[]));
+    let pause: PauseResp = multisig.query(&QueryMsg::GetPauseState {}).unwrap();
+    assert!(!pause.paused);
+    multisig.approve(bank_tx("recipient", 5)).unwrap();
+}
+
+#[test]
+fn test_unpause_needs_quorum() {
+    let mut multisig = MultisigApp::new();
+    multisig
+        .approve(TxMsg::TxSelf(SelfTx::SetThreshold {
+            category: TxCategory::SelfConfig,
+            threshold: Some(1),
+        }))
+        .unwrap();
+    multisig.execute(OWNERS[0], &ExecuteMsg::Pause {}).unwrap();
+
+    // A lowered threshold doesn't let a single owner lift the pause
+    let tx_id = multisig
+        .propose(OWNERS[1], TxMsg::TxSelf(SelfTx::Unpause {}))
+        .unwrap();
+    assert_eq!(
+        ContractError::NotEnoughSignatures {
+            quorum: 2,
+            num_signed: 1
+        },
+        multisig.execute_tx(OWNERS[1], tx_id).unwrap_err()
+    );
+    multisig.sign(OWNERS[2], tx_id).unwrap();
+    multisig.execute_tx(OWNERS[1], tx_id).unwrap();
+    let pause: PauseResp = multisig.query(&QueryMsg::GetPauseState {}).unwrap();
+    assert!(!pause.paused);
+}
+
+#[test]
+fn test_recovery() {
+    let mut multisig = with_guardians();
+    let tx_id = multisig
+        .propose(OWNERS[0], bank_tx("recipient", 5))
+        .unwrap();
+
+    assert_eq!(
+        ContractError::Unauthorized {},
+        propose_recovery(&mut multisig, OWNERS[0]).unwrap_err()
+    );
+    propose_recovery(&mut multisig, GUARDIANS[0]).unwrap();
+    assert_eq!(
+        ContractError::RecoveryInProgress,
+        propose_recovery(&mut multisig, GUARDIANS[1]).unwrap_err()
+    );
+
+    assert_eq!(
+        ContractError::NotEnoughGuardianApprovals {
+            threshold: 2,
+            approvals: 1
+        },
+        multisig
+            .execute(GUARDIANS[0], &ExecuteMsg::ExecuteRecovery {})
+            .unwrap_err()
+    );
+    assert_eq!(
+        ContractError::AlreadyApprovedRecovery,
+        multisig
+            .execute(GUARDIANS[0], &ExecuteMsg::ApproveRecovery {})
+            .unwrap_err()
+    );
+    multisig
+        .execute(GUARDIANS[1], &ExecuteMsg::ApproveRecovery {})
+        .unwrap();
+
+    let recovery: RecoveryResp = multisig.query(&QueryMsg::GetRecovery {}).unwrap();
+    let recovery = recovery.recovery.unwrap();
+    assert_eq!(
+        vec![addr(GUARDIANS[0]), addr(GUARDIANS[1])],
+        recovery.approvals
+    );
+    let ready_at = recovery.proposed_at.plus_seconds(RECOVERY_DELAY);
+    assert_eq!(
+        ContractError::RecoveryDelayNotPassed { ready_at },
+        multisig
+            .execute(GUARDIANS[2], &ExecuteMsg::ExecuteRecovery {})
+            .unwrap_err()
+    );
+
+    multisig.advance(RECOVERY_DELAY);
+    let res = multisig
+        .execute(GUARDIANS[2], &ExecuteMsg::ExecuteRecovery {})
+        .unwrap();
+
+    assert!(has_event(
+        &res,
+        "multisig.recovery",
+        "execute",
+        &[("quorum", "2")]
+    ));
+    assert!(has_event(
+        &res,
+        "multisig.owner",
+        "remove",
+        &[("owner", OWNERS[0])]
+    ));
+    let admins: ListAdminsResp = multisig.query(&QueryMsg::ListAdmins {}).unwrap();
+    assert_eq!(vec![addr("new_owner1"), addr("new_owner2")], admins.admins);
+    // Pending transactions were signed by the replaced owners, they are closed as rejected
+    assert!(multisig.pending().is_empty());
+    assert!(has_event(
+        &res,
+        "multisig.tx",
+        "close",
+        &[("tx_id", &tx_id.to_string()), ("status", "rejected")]
+    ));
+    assert_eq!(TxStatus::Rejected, multisig.completed()[0].status);
+    assert_eq!(
+        ContractError::NonExistentTx(tx_id),
+        multisig.sign("new_owner1", tx_id).unwrap_err()
+    );
+    // Along with their votes and cw3 proposal
+    let vote: VoteResp = multisig
+        .query(&QueryMsg::Vote {
+            proposal_id: tx_id as u64,
+            voter: OWNERS[0].to_string(),
+        })
+        .unwrap();
+    assert_eq!(None, vote.vote);
+    assert!(multisig
+        .query::<ListSignedResp>(&QueryMsg::ListSigned {
+            admin: addr(OWNERS[0]),
+            tx_id,
+        })
+        .is_err());
+    assert!(multisig
+        .query::<ProposalResp>(&QueryMsg::Proposal {
+            proposal_id: tx_id as u64,
+        })
+        .is_err());
+    let recovery: RecoveryResp = multisig.query(&QueryMsg::GetRecovery {}).unwrap();
+    assert_eq!(None, recovery.recovery);
+}
+
+#[test]
+fn test_recovery_failing_hook() {
+    let mut multisig = with_guardians();
+    // A wallet without a group rejects member-changed notifications
+    let hook = multisig.instantiate(&instantiate_msg()).unwrap();
+    multisig
+        .approve(TxMsg::TxSelf(SelfTx::AddHook { hook }))
+        .unwrap();
+
+    let res = multisig
+        .approve(TxMsg::TxSelf(SelfTx::AddOwner {
+            owner: addr("owner4"),
+            quorum: None,
+        }))
+        .unwrap();
+    assert!(has_event(&res, "multisig.owner", "add", &[]));
+    assert!(has_event(&res, "multisig.hook", "notify_failed", &[]));
+
+    propose_recovery(&mut multisig, GUARDIANS[0]).unwrap();
+    multisig
+        .execute(GUARDIANS[1], &ExecuteMsg::ApproveRecovery {})
+        .unwrap();
+    multisig.advance(RECOVERY_DELAY);
+    let res = multisig
+        .execute(GUARDIANS[2], &ExecuteMsg::ExecuteRecovery {})
+        .unwrap();
+    assert!(has_event(&res, "multisig.recovery", "execute", &[]));
+    assert!(has_event(&res, "multisig.hook", "notify_failed", &[]));
+    let admins: ListAdminsResp = multisig.query(&QueryMsg::ListAdmins {}).unwrap();
+    assert_eq!(vec![addr("new_owner1"), addr("new_owner2")], admins.admins);
+}
+
+#[test]
+fn test_recovery_invalid() {
+    let mut multisig = with_guardians();
+
+    for msg in [
+        ExecuteMsg::ApproveRecovery {},
+        ExecuteMsg::ExecuteRecovery {},
+    ] {
+        assert_eq!(
+            ContractError::NoRecovery,
+            multisig.execute(GUARDIANS[0], &msg).unwrap_err()
+        );
+    }
+    assert_eq!(
+        ContractError::NoRecovery,
+        multisig
+            .execute(OWNERS[0], &ExecuteMsg::VetoRecovery {})
+            .unwrap_err()
+    );
+
+    let msg = ExecuteMsg::ProposeRecovery {
+        owners: vec![],
+        quorum: 1,
+    };
+    assert_eq!(
+        ContractError::ZeroOwners,
+        multisig.execute(GUARDIANS[0], &msg).unwrap_err()
+    );
+    let msg = ExecuteMsg::ProposeRecovery {
+        owners: vec![addr("new_owner1")],
+        quorum: 2,
+    };
+    assert_eq!(
+        ContractError::WrongQuorum {
+            quorum: 2,
+            owners: 1
+        },
+        multisig.execute(GUARDIANS[0], &msg).unwrap_err()
+    );
+
+    // Only a wallet with guardians can be recovered
+    let mut multisig = MultisigApp::new();
+    assert_eq!(
+        ContractError::NoGuardians,
+        propose_recovery(&mut multisig, GUARDIANS[0]).unwrap_err()
+    );
+}
+
+#[test]
+fn test_veto_recovery() {
+    let mut multisig = with_guardians();
+    propose_recovery(&mut multisig, GUARDIANS[0]).unwrap();
+
+    assert_eq!(
+        ContractError::Unauthorized {},
+        multisig
+            .execute(GUARDIANS[1], &ExecuteMsg::VetoRecovery {})
+            .unwrap_err()
+    );
+    let res = multisig
+        .execute(OWNERS[1], &ExecuteMsg::VetoRecovery {})
+        .unwrap();
+
+    assert!(has_event(
+        &res,
+        "multisig.recovery",
+        "veto",
+        &[("actor", OWNERS[1])]
+    ));
+    let recovery: RecoveryResp = multisig.query(&QueryMsg::GetRecovery {}).unwrap();
+    assert_eq!(None, recovery.recovery);
+}
+
+#[test]
+fn test_update_guardians() {
+    let mut multisig = MultisigApp::new();
+
+    multisig
+        .approve(TxMsg::TxSelf(SelfTx::UpdateGuardians {
+            guardians: Some(guardian_set()),
+        }))
+        .unwrap();
+    let resp: GuardiansResp = multisig.query(&QueryMsg::GetGuardians {}).unwrap();
+    assert_eq!(Some(guardian_set()), resp.guardians);
+
+    // Updating the guardians cancels the recovery in progress
+    propose_recovery(&mut multisig, GUARDIANS[0]).unwrap();
+    multisig
+        .approve(TxMsg::TxSelf(SelfTx::UpdateGuardians { guardians: None }))
+        .unwrap();
+    let resp: GuardiansResp = multisig.query(&QueryMsg::GetGuardians {}).unwrap();
+    assert_eq!(None, resp.guardians);
+    let recovery: RecoveryResp = multisig.query(&QueryMsg::GetRecovery {}).unwrap();
+    assert_eq!(None, recovery.recovery);
+
+    let err = multisig
+        .approve(TxMsg::TxSelf(SelfTx::UpdateGuardians {
+            guardians: Some(GuardianSet {
+                guardians: vec![],
+                threshold: 1,
+                recovery_delay: 0,
+            }),
+        }))
+        .unwrap_err();
+    assert_eq!(ContractError::ZeroGuardians, err);
+}
+
+#[test]
+fn test_rate_limit() {
+    let mut msg = instantiate_msg();
+    msg.rate_limit_delay = Some(100);
+    let mut multisig = MultisigApp::with_msg(msg);
+
+    multisig
+        .approve(TxMsg::TxSelf(SelfTx::SetRateLimit {
+            denom: DENOM.to_string(),
+            limit: rate_limit(50, 1000),
+        }))
+        .unwrap();
+    multisig.approve(bank_tx("recipient", 30)).unwrap();
+
+    assert_eq!(
+        ContractError::RateLimitExceeded {
+            denom: DENOM.to_string(),
+            available: Uint128::new(20),
+            required: Uint128::new(30)
+        },
+        multisig.approve(bank_tx("recipient", 30)).unwrap_err()
+    );
+    let resp = get_rate_limit(&multisig);
+    assert_eq!(Uint128::new(30), resp.used);
+    assert_eq!(Some(Uint128::new(20)), resp.available);
+
+    // The outflow leaves the window
+    multisig.advance(1000);
+    let tx_id = multisig.pending()[0].id;
+    multisig.execute_tx(OWNERS[0], tx_id).unwrap();
+    assert_eq!(60, multisig.balance("recipient"));
+}
+
+#[test]
+fn test_rate_limit_timelock() {
+    let mut msg = instantiate_msg();
+    msg.rate_limit_delay = Some(100);
+    let mut multisig = MultisigApp::with_msg(msg);
+    multisig
+        .approve(TxMsg::TxSelf(SelfTx::SetRateLimit {
+            denom: DENOM.to_string(),
+            limit: rate_limit(50, 1000),
+        }))
+        .unwrap();
+
+    // Loosening the limit waits for the delay
+    multisig
+        .approve(TxMsg::TxSelf(SelfTx::SetRateLimit {
+            denom: DENOM.to_string(),
+            limit: rate_limit(500, 1000),
+        }))
+        .unwrap();
+    let resp = get_rate_limit(&multisig);
+    assert_eq!(rate_limit(50, 1000), resp.limit);
+    assert!(resp.pending.is_some());
+
+    multisig.advance(100);
+    let resp = get_rate_limit(&multisig);
+    assert_eq!(rate_limit(500, 1000), resp.limit);
+    assert_eq!(None, resp.pending);
+    multisig.approve(bank_tx("recipient", 100)).unwrap();
+
+    // Tightening it applies right away
+    multisig
+        .approve(TxMsg::TxSelf(SelfTx::SetRateLimit {
+            denom: DENOM.to_string(),
+            limit: rate_limit(100, 1000),
+        }))
+        .unwrap();
+    assert!(multisig.approve(bank_tx("recipient", 1)).is_err());
+
+    // Removing it is a loosening too
+    multisig
+        .approve(TxMsg::TxSelf(SelfTx::SetRateLimit {
+            denom: DENOM.to_string(),
+            limit: None,
+        }))
+        .unwrap();
+    assert_eq!(rate_limit(100, 1000), get_rate_limit(&multisig).limit);
+    multisig.advance(100);
+    assert_eq!(None, get_rate_limit(&multisig).limit);
+}
+
+#[test]
+fn test_rate_limit_invalid() {
+    let mut multisig = MultisigApp::new();
+
+    let err = multisig
+        .approve(TxMsg::TxSelf(SelfTx::SetRateLimit {
+            denom: DENOM.to_string(),
+            limit: rate_limit(50, 0),
+        }))
+        .unwrap_err();
+    assert_eq!(ContractError::InvalidRateLimit, err);
+}
@@ -0,0 +1,489 @@
+use cosmwasm_std::testing::{mock_dependencies, mock_env};
+use cosmwasm_std::{coins, Addr, Reply, StdError, SubMsgResult};
+use cw_multi_test::Executor;
+use multisig::contract::reply;
+use multisig::msg::{
+    ConfigResp, ExecuteMsg, ListAdminsResp, ListRolesResp, ListSignedResp, QueryMsg, RoleMember,
+    RolesResp,
+};
+use multisig::state::{GuardianSet, Role, SelfTx, Transaction, TxMsg, TxStatus};
+use multisig::ContractError;
+
+mod common;
+use common::{addr, bank_tx, has_event, instantiate_msg, MultisigApp, DENOM, OWNERS, WALLET_FUNDS};
+
+#[test]
+fn test_instantiate() {
+    let multisig = MultisigApp::new();
+
+    assert_eq!(WALLET_FUNDS, multisig.wallet_balance());
+    let admins: ListAdminsResp = multisig.query(&QueryMsg::ListAdmins {}).unwrap();
+    assert_eq!(instantiate_msg().owners, admins.admins);
+    let config: ConfigResp = multisig.query(&QueryMsg::GetConfig {}).unwrap();
+    assert_eq!(2, config.quorum);
+    assert!(!config.auto_execute);
+    assert!(!config.permissionless_execution);
+    assert!(!config.allowlist_mode);
+    assert!(multisig.pending().is_empty());
+}
+
+#[test]
+fn test_instantiate_invalid() {
+    let mut multisig = MultisigApp::new();
+
+    let mut msg = instantiate_msg();
+    msg.owners = vec![];
+    assert_eq!(
+        ContractError::ZeroOwners,
+        multisig.instantiate(&msg).unwrap_err()
+    );
+
+    let mut msg = instantiate_msg();
+    msg.quorum = 4;
+    assert_eq!(
+        ContractError::WrongQuorum {
+            quorum: 4,
+            owners: 3
+        },
+        multisig.instantiate(&msg).unwrap_err()
+    );
+
+    let mut msg = instantiate_msg();
+    msg.guardians = Some(GuardianSet {
+        guardians: vec![],
+        threshold: 1,
+        recovery_delay: 0,
+    });
+    assert_eq!(
+        ContractError::ZeroGuardians,
+        multisig.instantiate(&msg).unwrap_err()
+    );
+
+    msg.guardians = Some(GuardianSet {
+        guardians: vec![addr("guardian")],
+        threshold: 2,
+        recovery_delay: 0,
+    });
+    assert_eq!(
+        ContractError::WrongGuardianThreshold {
+            threshold: 2,
+            guardians: 1
+        },
+        multisig.instantiate(&msg).unwrap_err()
+    );
+}
+
+#[test]
+fn test_events() {
+    let mut multisig = MultisigApp::new();
+
+    let msg = ExecuteMsg::CreateTransaction {
+        tx_msg: bank_tx("recipient", 10),
+        auto_execute: None,
+    };
+    let res = multisig.execute(OWNERS[0], &msg).unwrap();
+    let tx_id = multisig.last_tx_id();
+    assert!(has_event(
+        &res,
+        "multisig.tx",
+        "propose",
+        &[
+            ("tx_id", &tx_id.to_string()),
+            ("actor", OWNERS[0]),
+            ("status", "pending"),
+            ("confirmations", "1"),
+            ("msg_type", "bank"),
+            ("to", "recipient"),
+            ("amount", "10usei"),
+        ]
+    ));
+
+    let res = multisig.sign(OWNERS[1], tx_id).unwrap();
+    assert!(has_event(
+        &res,
+        "multisig.tx",
+        "sign",
+        &[
+            ("tx_id", &tx_id.to_string()),
+            ("actor", OWNERS[1]),
+            ("confirmations", "2")
+        ]
+    ));
+
+    let res = multisig.execute_tx(OWNERS[2], tx_id).unwrap();
+    assert!(has_event(
+        &res,
+        "multisig.tx",
+        "execute",
+        &[("tx_id", &tx_id.to_string()), ("actor", OWNERS[2])]
+    ));
+    assert!(has_event(
+        &res,
+        "multisig.tx",
+        "executed",
+        &[("tx_id", &tx_id.to_string()), ("status", "executed")]
+    ));
+
+    // Settings that are removed are reported as none
+    let res = multisig
+        .approve(TxMsg::TxSelf(SelfTx::UpdateGuardians { guardians: None }))
+        .unwrap();
+    assert!(has_event(
+        &res,
+        "multisig.config",
+        "update_guardians",
+        &[("guardians", "none")]
+    ));
+}
+
+#[test]
+fn test_propose() {
+    let mut multisig = MultisigApp::new();
+
+    let tx_id = multisig
+        .propose(OWNERS[0], bank_tx("recipient", 5))
+        .unwrap();
+
+    let mut tx = Transaction::new(bank_tx("recipient", 5), tx_id);
+    tx.num_confirmations = 1;
+    assert_eq!(vec![tx], multisig.pending());
+    let signed: ListSignedResp = multisig
+        .query(&QueryMsg::ListSigned {
+            admin: addr(OWNERS[0]),
+            tx_id,
+        })
+        .unwrap();
+    assert!(signed.signed);
+}
+
+#[test]
+fn test_propose_unauthorized() {
+    let mut multisig = MultisigApp::new();
+
+    let err = multisig
+        .propose("stranger", bank_tx("recipient", 5))
+        .unwrap_err();
+    assert_eq!(ContractError::Unauthorized {}, err);
+}
+
+#[test]
+fn test_sign() {
+    let mut multisig = MultisigApp::new();
+    let tx_id = multisig
+        .propose(OWNERS[0], bank_tx("recipient", 5))
+        .unwrap();
+
+    let res = multisig.sign(OWNERS[1], tx_id).unwrap();
+
+    assert!(has_event(
+        &res,
+        "multisig.tx",
+        "sign",
+        &[("actor", OWNERS[1]), ("confirmations", "2")]
+    ));
+    assert_eq!(2, multisig.tx(tx_id).num_confirmations);
+    let signed: ListSignedResp = multisig
+        .query(&QueryMsg::ListSigned {
+            admin: addr(OWNERS[1]),
+            tx_id,
+        })
+        .unwrap();
+    assert!(signed.signed);
+}
+
+#[test]
+fn test_sign_invalid() {
+    let mut multisig = MultisigApp::new();
+    let tx_id = multisig
+        .propose(OWNERS[0], bank_tx("recipient", 5))
+        .unwrap();
+
+    assert_eq!(
+        ContractError::AlreadySigned(tx_id),
+        multisig.sign(OWNERS[0], tx_id).unwrap_err()
+    );
+    assert_eq!(
+        ContractError::Unauthorized {},
+        multisig.sign("stranger", tx_id).unwrap_err()
+    );
+    assert_eq!(
+        ContractError::NonExistentTx(99),
+        multisig.sign(OWNERS[1], 99).unwrap_err()
+    );
+}
+
+#[test]
+fn test_execute() {
+    let mut multisig = MultisigApp::new();
+    let tx_id = multisig
+        .propose(OWNERS[0], bank_tx("recipient", 5))
+        .unwrap();
+
+    assert_eq!(
+        ContractError::NotEnoughSignatures {
+            quorum: 2,
+            num_signed: 1
+        },
+        multisig.execute_tx(OWNERS[0], tx_id).unwrap_err()
+    );
+
+    multisig.sign(OWNERS[1], tx_id).unwrap();
+    let res = multisig.execute_tx(OWNERS[2], tx_id).unwrap();
+
+    assert!(has_event(
+        &res,
+        "multisig.tx",
+        "executed",
+        &[("actor", OWNERS[2])]
+    ));
+    assert_eq!(5, multisig.balance("recipient"));
+    assert_eq!(WALLET_FUNDS - 5, multisig.wallet_balance());
+    assert!(multisig.pending().is_empty());
+    let completed = multisig.completed();
+    assert_eq!(TxStatus::Executed, completed[0].status);
+    assert_eq!(Some(addr(OWNERS[2])), completed[0].executor);
+
+    assert_eq!(
+        ContractError::NonExistentTx(tx_id),
+        multisig.execute_tx(OWNERS[0], tx_id).unwrap_err()
+    );
+}
+
+#[test]
+fn test_permissionless_execution() {
+    let mut multisig = MultisigApp::new();
+    let tx_id = multisig
+        .propose(OWNERS[0], bank_tx("recipient", 5))
+        .unwrap();
+    multisig.sign(OWNERS[1], tx_id).unwrap();
+
+    assert_eq!(
+        ContractError::Unauthorized {},
+        multisig.execute_tx("relayer", tx_id).unwrap_err()
+    );
+
+    multisig
+        .approve(TxMsg::TxSelf(SelfTx::SetPermissionlessExecution {
+            enabled: true,
+        }))
+        .unwrap();
+    multisig.execute_tx("relayer", tx_id).unwrap();
+    assert_eq!(5, multisig.balance("recipient"));
+}
+
+#[test]
+fn test_failed_execution_stays_pending() {
+    let mut multisig = MultisigApp::new();
+    let tx_msg = bank_tx("recipient", WALLET_FUNDS + 1);
+
+    let res = multisig.approve(tx_msg).unwrap();
+
+    assert!(has_event(&res, "multisig.tx", "execution_failed", &[]));
+    let tx_id = multisig.last_tx_id();
+    assert!(matches!(
+        multisig.tx(tx_id).status,
+        TxStatus::ExecutionFailed { .. }
+    ));
+
+    // Once the wallet has the funds the transaction can be retried
+    multisig
+        .app
+        .send_tokens(addr("funder"), multisig.wallet.clone(), &coins(1, DENOM))
+        .unwrap();
+    multisig.execute_tx(OWNERS[0], tx_id).unwrap();
+    assert_eq!(WALLET_FUNDS + 1, multisig.balance("recipient"));
+    assert_eq!(TxStatus::Executed, multisig.completed()[0].status);
+}
+
+#[test]
+fn test_auto_execute() {
+    let mut msg = instantiate_msg();
+    msg.auto_execute = Some(true);
+    let mut multisig = MultisigApp::with_msg(msg);
+
+    let tx_id = multisig
+        .propose(OWNERS[0], bank_tx("recipient", 5))
+        .unwrap();
+    multisig.sign(OWNERS[1], tx_id).unwrap();
+    assert_eq!(5, multisig.balance("recipient"));
+    assert!(multisig.pending().is_empty());
+
+    // A transaction can opt out
+    let msg = ExecuteMsg::CreateTransaction {
+        tx_msg: bank_tx("recipient", 5),
+        auto_execute: Some(false),
+    };
+    multisig.execute(OWNERS[0], &msg).unwrap();
+    let tx_id = multisig.last_tx_id();
+    multisig.sign(OWNERS[1], tx_id).unwrap();
+    assert_eq!(2, multisig.tx(tx_id).num_confirmations);
+
+    multisig
+        .approve(TxMsg::TxSelf(SelfTx::SetAutoExecute { enabled: false }))
+        .unwrap();
+    let config: ConfigResp = multisig.query(&QueryMsg::GetConfig {}).unwrap();
+    assert!(!config.auto_execute);
+}
+
+#[test]
+fn test_failed_auto_execute_keeps_signature() {
+    let mut msg = instantiate_msg();
+    msg.auto_execute = Some(true);
+    let mut multisig = MultisigApp::with_msg(msg);
+
+    multisig
+        .approve(TxMsg::TxSelf(SelfTx::SetAllowlistMode { enabled: true }))
+        .unwrap();
+    let tx_id = multisig
+        .propose(OWNERS[0], bank_tx("recipient", 5))
+        .unwrap();
+    let res = multisig.sign(OWNERS[1], tx_id).unwrap();
+
+    assert!(has_event(&res, "multisig.tx", "auto_execute_failed", &[]));
+    assert_eq!(2, multisig.tx(tx_id).num_confirmations);
+}
+
+#[test]
+fn test_roles() {
+    let mut multisig = MultisigApp::new();
+
+    multisig
+        .approve(TxMsg::TxSelf(SelfTx::GrantRole {
+            addr: addr("proposer"),
+            role: Role::Proposer,
+        }))
+        .unwrap();
+    multisig
+        .approve(TxMsg::TxSelf(SelfTx::GrantRole {
+            addr: addr("proposer"),
+            role: Role::Executor,
+        }))
+        .unwrap();
+
+    let roles: RolesResp = multisig
+        .query(&QueryMsg::GetRoles {
+            addr: addr("proposer"),
+        })
+        .unwrap();
+    assert_eq!(vec![Role::Proposer, Role::Executor], roles.roles);
+    let roles: RolesResp = multisig
+        .query(&QueryMsg::GetRoles {
+            addr: addr(OWNERS[0]),
+        })
+        .unwrap();
+    assert_eq!(3, roles.roles.len());
+
+    // Proposers aren't owners, their proposals start without signatures
+    let tx_id = multisig
+        .propose("proposer", bank_tx("recipient", 5))
+        .unwrap();
+    assert_eq!(0, multisig.tx(tx_id).num_confirmations);
+    assert_eq!(
+        ContractError::Unauthorized {},
+        multisig.sign("proposer", tx_id).unwrap_err()
+    );
+    multisig.sign(OWNERS[0], tx_id).unwrap();
+    multisig.sign(OWNERS[1], tx_id).unwrap();
+    multisig.execute_tx("proposer", tx_id).unwrap();
+    assert_eq!(5, multisig.balance("recipient"));
+
+    multisig
+        .approve(TxMsg::TxSelf(SelfTx::RevokeRole {
+            addr: addr("proposer"),
+            role: Role::Proposer,
+        }))
+        .unwrap();
+    let roles: ListRolesResp = multisig
+        .query(&QueryMsg::ListRoles {
+            start_after: None,
+            limit: None,
+        })
+        .unwrap();
+    assert_eq!(
+        vec![RoleMember {
+            addr: addr("proposer"),
+            roles: vec![Role::Executor],
+        }],
+        roles.members
+    );
+    assert_eq!(
+        ContractError::Unauthorized {},
+        multisig
+            .propose("proposer", bank_tx("recipient", 5))
+            .unwrap_err()
+    );
+}
+
+#[test]
+fn test_roles_invalid() {
+    let mut multisig = MultisigApp::new();
+
+    let err = multisig
+        .approve(TxMsg::TxSelf(SelfTx::GrantRole {
+            addr: addr("signer"),
+            role: Role::Signer,
+        }))
+        .unwrap_err();
+    assert_eq!(ContractError::SignerRoleIsOwnership, err);
+
+    let err = multisig
+        .approve(TxMsg::TxSelf(SelfTx::RevokeRole {
+            addr: addr("executor"),
+            role: Role::Executor,
+        }))
+        .unwrap_err();
+    assert_eq!(
+        ContractError::RoleNotGranted {
+            addr: "executor".to_string(),
+            role: Role::Executor
+        },
+        err
+    );
+}
+
+#[test]
+fn test_completed_pagination() {
+    let mut multisig = MultisigApp::new();
+    for amount in 1..=3 {
+        multisig.approve(bank_tx("recipient", amount)).unwrap();
+    }
+
+    let resp: multisig::msg::ListCompletedTxsResp = multisig
+        .query(&QueryMsg::ListCompletedTxs {
+            offset: Some(1),
+            limit: Some(1),
+        })
+        .unwrap();
+    // Completed transactions are listed oldest first
+    let ids: Vec<u128> = resp.transactions.0.iter().map(|tx| tx.id).collect();
+    assert_eq!(vec![2], ids);
+    assert_eq!(bank_tx("recipient", 2), resp.transactions.0[0].tx_msg);
+    assert_eq!(6, multisig.balance("recipient"));
+}
+
+#[test]
+fn test_unknown_reply_id() {
+    let mut deps = mock_dependencies();
+
+    let msg = Reply {
+        id: 99,
+        result: SubMsgResult::Err("error".to_string()),
+    };
+    let err = reply(deps.as_mut(), mock_env(), msg).unwrap_err();
+    assert_eq!(ContractError::UnknownReplyID {}, err);
+}
+
+#[test]
+fn test_std_error() {
+    let mut multisig = MultisigApp::new();
+
+    // The group must answer the cw4 queries
+    let mut msg = instantiate_msg();
+    msg.owners = vec![];
+    msg.group = Some(Addr::unchecked("not_a_contract"));
+    let err = multisig.instantiate(&msg).unwrap_err();
+    assert!(
+        matches!(err, ContractError::Std(StdError::GenericErr { .. })),
+        "{err:?}"
+    );
+}
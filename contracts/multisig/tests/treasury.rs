@@ -0,0 +1,928 @@
+use cosmwasm_std::{
+    coins, to_json_binary, BankMsg, Binary, Coin, CosmosMsg, DistributionMsg, IbcMsg, StakingMsg,
+    Timestamp, Uint128, WasmMsg,
+};
+use multisig::msg::{
+    ExecuteMsg, ListModulesResp, ListSchedulesResp, QueryMsg, ReservedResp, StreamResp,
+};
+use multisig::state::{
+    Authorization, RateLimit, Schedule, SelfTx, TransferTimeout, TxMsg, TxStatus,
+};
+use multisig::ContractError;
+
+mod common;
+use common::{addr, bank_tx, has_event, instantiate_msg, MultisigApp, DENOM, OWNERS, WALLET_FUNDS};
+
+fn now(multisig: &MultisigApp) -> Timestamp {
+    multisig.app.block_info().time
+}
+
+fn create_schedule(multisig: &mut MultisigApp, interval: u64, count: u32) {
+    let start = now(multisig);
+    multisig
+        .approve(TxMsg::TxSelf(SelfTx::CreateSchedule {
+            msg: Box::new(bank_tx("payee", 10)),
+            start,
+            interval,
+            count,
+        }))
+        .unwrap();
+}
+
+fn create_stream(multisig: &mut MultisigApp, amount: u128, duration: u64) {
+    let start = now(multisig);
+    multisig
+        .approve(TxMsg::TxSelf(SelfTx::CreateStream {
+            recipient: addr("payee"),
+            amount: Coin::new(amount, DENOM),
+            start,
+            end: start.plus_seconds(duration),
+        }))
+        .unwrap();
+}
+
+fn send(to: &str, amount: u128) -> CosmosMsg {
+    BankMsg::Send {
+        to_address: to.to_string(),
+        amount: coins(amount, DENOM),
+    }
+    .into()
+}
+
+fn claim_stream(
+    multisig: &mut MultisigApp,
+    sender: &str,
+    amount: Option<u128>,
+) -> Result<cw_multi_test::AppResponse, ContractError> {
+    multisig.execute(
+        sender,
+        &ExecuteMsg::ClaimStream {
+            id: 1,
+            amount: amount.map(Uint128::new),
+        },
+    )
+}
+
+fn execute_from_module(
+    multisig: &mut MultisigApp,
+    msgs: Vec<CosmosMsg>,
+) -> Result<cw_multi_test::AppResponse, ContractError> {
+    multisig.execute("module", &ExecuteMsg::ExecuteFromModule { msgs })
+}
+
+#[test]
+fn test_schedule() {
+    let mut multisig = MultisigApp::new();
+    create_schedule(&mut multisig, 100, 3);
+
+    let schedule: Schedule = multisig.query(&QueryMsg::GetSchedule { id: 1 }).unwrap();
+    assert_eq!(3, schedule.count);
+    assert_eq!(0, schedule.executed);
+
+    // Anyone can run the due payments
+    let res = multisig
+        .execute("keeper", &ExecuteMsg::RunDueSchedules {})
+        .unwrap();
+    assert!(has_event(
+        &res,
+        "multisig.schedule",
+        "run",
+        &[("payments", "1"), ("remaining", "2")]
+    ));
+    assert_eq!(10, multisig.balance("payee"));
+
+    // Payments that piled up are made at once, and nothing is due twice
+    multisig.advance(250);
+    multisig
+        .execute("keeper", &ExecuteMsg::RunDueSchedules {})
+        .unwrap();
+    multisig
+        .execute("keeper", &ExecuteMsg::RunDueSchedules {})
+        .unwrap();
+    assert_eq!(30, multisig.balance("payee"));
+
+    let schedules: ListSchedulesResp = multisig
+        .query(&QueryMsg::ListSchedules {
+            start_after: None,
+            limit: None,
+        })
+        .unwrap();
+    assert!(schedules.schedules.is_empty());
+    assert_eq!(
+        ContractError::NonExistentSchedule(1),
+        multisig
+            .approve(TxMsg::TxSelf(SelfTx::CancelSchedule { id: 1 }))
+            .unwrap_err()
+    );
+}
+
+#[test]
+fn test_cancel_schedule() {
+    let mut multisig = MultisigApp::new();
+    create_schedule(&mut multisig, 100, 3);
+    multisig
+        .execute("keeper", &ExecuteMsg::RunDueSchedules {})
+        .unwrap();
+
+    let res = multisig
+        .approve(TxMsg::TxSelf(SelfTx::CancelSchedule { id: 1 }))
+        .unwrap();
+
+    assert!(has_event(
+        &res,
+        "multisig.schedule",
+        "cancel",
+        &[("schedule_id", "1"), ("remaining", "2")]
+    ));
+    multisig.advance(1000);
+    multisig
+        .execute("keeper", &ExecuteMsg::RunDueSchedules {})
+        .unwrap();
+    assert_eq!(10, multisig.balance("payee"));
+}
+
+#[test]
+fn test_schedule_limited() {
+    let mut multisig = MultisigApp::new();
+    create_schedule(&mut multisig, 100, 3);
+    let start = now(&multisig);
+    multisig
+        .approve(TxMsg::TxSelf(SelfTx::CreateSchedule {
+            msg: Box::new(bank_tx("other", 10)),
+            start,
+            interval: 100,
+            count: 3,
+        }))
+        .unwrap();
+    multisig
+        .approve(TxMsg::TxSelf(SelfTx::SetRateLimit {
+            denom: DENOM.to_string(),
+            limit: Some(RateLimit {
+                amount: Uint128::new(25),
+                window: 1000,
+            }),
+        }))
+        .unwrap();
+
+    // Only the payments the rate limit allows are made, the rest stay due
+    multisig.advance(250);
+    let res = multisig
+        .execute("keeper", &ExecuteMsg::RunDueSchedules {})
+        .unwrap();
+    assert!(has_event(
+        &res,
+        "multisig.schedule",
+        "run",
+        &[("schedule_id", "1"), ("payments", "2"), ("remaining", "1")]
+    ));
+    assert!(has_event(
+        &res,
+        "multisig.schedule",
+        "skip",
+        &[
+            ("schedule_id", "2"),
+            ("reason", &ContractError::ScheduleLimited.to_string())
+        ]
+    ));
+    assert_eq!(20, multisig.balance("payee"));
+    assert_eq!(0, multisig.balance("other"));
+
+    // A recipient that is no longer allowed doesn't hold up the other schedules
+    multisig
+        .approve(TxMsg::TxSelf(SelfTx::AllowRecipients {
+            addrs: vec![addr("payee")],
+        }))
+        .unwrap();
+    multisig
+        .approve(TxMsg::TxSelf(SelfTx::SetAllowlistMode { enabled: true }))
+        .unwrap();
+    multisig.advance(1000);
+    let res = multisig
+        .execute("keeper", &ExecuteMsg::RunDueSchedules {})
+        .unwrap();
+    assert!(has_event(
+        &res,
+        "multisig.schedule",
+        "skip",
+        &[
+            ("schedule_id", "2"),
+            (
+                "reason",
+                &ContractError::RecipientNotAllowed(addr("other").to_string()).to_string()
+            )
+        ]
+    ));
+    assert_eq!(30, multisig.balance("payee"));
+    assert_eq!(0, multisig.balance("other"));
+}
+
+#[test]
+fn test_schedule_invalid() {
+    let mut multisig = MultisigApp::new();
+    let start = now(&multisig);
+
+    let err = multisig
+        .approve(TxMsg::TxSelf(SelfTx::CreateSchedule {
+            msg: Box::new(TxMsg::TxSelf(SelfTx::Unpause {})),
+            start,
+            interval: 100,
+            count: 3,
+        }))
+        .unwrap_err();
+    assert_eq!(ContractError::UnschedulableMsg, err);
+
+    for (interval, count) in [(0, 3), (100, 0)] {
+        let err = multisig
+            .approve(TxMsg::TxSelf(SelfTx::CreateSchedule {
+                msg: Box::new(bank_tx("payee", 10)),
+                start,
+                interval,
+                count,
+            }))
+            .unwrap_err();
+        assert_eq!(ContractError::InvalidSchedule, err);
+    }
+
+    // Payments without coins or of zero would fail on every run
+    for coins in [vec![], vec![Coin::new(10, DENOM), Coin::new(0, "uatom")]] {
+        let err = multisig
+            .approve(TxMsg::TxSelf(SelfTx::CreateSchedule {
+                msg: Box::new(TxMsg::TxBank {
+                    to: addr("payee"),
+                    coins,
+                }),
+                start,
+                interval: 100,
+                count: 3,
+            }))
+            .unwrap_err();
+        assert_eq!(ContractError::InvalidSchedule, err);
+    }
+}
+
+#[test]
+fn test_stream() {
+    let mut multisig = MultisigApp::new();
+    create_stream(&mut multisig, 600, 100);
+
+    let reserved: ReservedResp = multisig.query(&QueryMsg::GetReserved {}).unwrap();
+    assert_eq!(coins(600, DENOM), reserved.reserved);
+    // Reserved funds can't be spent
+    assert_eq!(
+        ContractError::InsufficientFunds {
+            denom: DENOM.to_string(),
+            available: Uint128::new(400),
+            required: Uint128::new(500)
+        },
+        multisig.approve(bank_tx("recipient", 500)).unwrap_err()
+    );
+
+    assert_eq!(
+        ContractError::NothingToClaim(1),
+        claim_stream(&mut multisig, "payee", None).unwrap_err()
+    );
+    multisig.advance(50);
+    assert_eq!(
+        ContractError::Unauthorized {},
+        claim_stream(&mut multisig, "stranger", None).unwrap_err()
+    );
+    claim_stream(&mut multisig, "payee", None).unwrap();
+    assert_eq!(300, multisig.balance("payee"));
+
+    let stream: StreamResp = multisig.query(&QueryMsg::GetStream { id: 1 }).unwrap();
+    assert_eq!(Uint128::new(300), stream.stream.claimed);
+    assert_eq!(Uint128::zero(), stream.claimable);
+
+    // The stream is removed once fully claimed
+    multisig.advance(50);
+    claim_stream(&mut multisig, "payee", None).unwrap();
+    assert_eq!(600, multisig.balance("payee"));
+    assert_eq!(
+        ContractError::NonExistentStream(1),
+        claim_stream(&mut multisig, "payee", None).unwrap_err()
+    );
+    let reserved: ReservedResp = multisig.query(&QueryMsg::GetReserved {}).unwrap();
+    assert!(reserved.reserved.is_empty());
+}
+
+#[test]
+fn test_claim_stream_partially() {
+    let mut multisig = MultisigApp::new();
+    create_stream(&mut multisig, 600, 100);
+    multisig
+        .approve(TxMsg::TxSelf(SelfTx::SetRateLimit {
+            denom: DENOM.to_string(),
+            limit: Some(RateLimit {
+                amount: Uint128::new(200),
+                window: 1000,
+            }),
+        }))
+        .unwrap();
+    multisig.advance(50);
+
+    // A claim above the rate limit can be split
+    assert_eq!(
+        ContractError::RateLimitExceeded {
+            denom: DENOM.to_string(),
+            available: Uint128::new(200),
+            required: Uint128::new(300)
+        },
+        claim_stream(&mut multisig, "payee", None).unwrap_err()
+    );
+    let res = claim_stream(&mut multisig, "payee", Some(200)).unwrap();
+    assert!(has_event(
+        &res,
+        "multisig.stream",
+        "claim",
+        &[("amount", "200usei"), ("claimed", "200")]
+    ));
+    assert_eq!(200, multisig.balance("payee"));
+
+    let stream: StreamResp = multisig.query(&QueryMsg::GetStream { id: 1 }).unwrap();
+    assert_eq!(Uint128::new(100), stream.claimable);
+    assert_eq!(
+        ContractError::NothingToClaim(1),
+        claim_stream(&mut multisig, "payee", Some(0)).unwrap_err()
+    );
+
+    // The rest is claimed once the window moves on, never more than vested
+    multisig.advance(1000);
+    claim_stream(&mut multisig, "payee", Some(200)).unwrap();
+    multisig.advance(1000);
+    claim_stream(&mut multisig, "payee", Some(1000)).unwrap();
+    assert_eq!(600, multisig.balance("payee"));
+}
+
+#[test]
+fn test_cancel_stream() {
+    let mut multisig = MultisigApp::new();
+    create_stream(&mut multisig, 600, 100);
+    multisig.advance(25);
+    claim_stream(&mut multisig, "payee", None).unwrap();
+    multisig.advance(50);
+
+    let res = multisig
+        .approve(TxMsg::TxSelf(SelfTx::CancelStream { id: 1 }))
+        .unwrap();
+
+    // The vested part goes to the recipient and the rest is released
+    assert!(has_event(
+        &res,
+        "multisig.stream",
+        "cancel",
+        &[
+            ("amount", "300usei"),
+            ("claimed", "150"),
+            ("returned", "150usei")
+        ]
+    ));
+    assert_eq!(450, multisig.balance("payee"));
+    assert_eq!(WALLET_FUNDS - 450, multisig.wallet_balance());
+    let reserved: ReservedResp = multisig.query(&QueryMsg::GetReserved {}).unwrap();
+    assert!(reserved.reserved.is_empty());
+
+    assert_eq!(
+        ContractError::NonExistentStream(1),
+        multisig
+            .approve(TxMsg::TxSelf(SelfTx::CancelStream { id: 1 }))
+            .unwrap_err()
+    );
+}
+
+#[test]
+fn test_cancel_stream_not_allowed() {
+    let mut multisig = MultisigApp::new();
+    create_stream(&mut multisig, 600, 100);
+    multisig.advance(50);
+    multisig
+        .approve(TxMsg::TxSelf(SelfTx::SetAllowlistMode { enabled: true }))
+        .unwrap();
+
+    // The vested part stays in the treasury with the rest
+    let res = multisig
+        .approve(TxMsg::TxSelf(SelfTx::CancelStream { id: 1 }))
+        .unwrap();
+    assert!(has_event(
+        &res,
+        "multisig.stream",
+        "cancel",
+        &[("amount", "0usei"), ("returned", "600usei")]
+    ));
+    assert_eq!(0, multisig.balance("payee"));
+    assert_eq!(WALLET_FUNDS, multisig.wallet_balance());
+    let reserved: ReservedResp = multisig.query(&QueryMsg::GetReserved {}).unwrap();
+    assert!(reserved.reserved.is_empty());
+}
+
+#[test]
+fn test_stream_invalid() {
+    let mut multisig = MultisigApp::new();
+    let start = now(&multisig);
+
+    for (amount, end) in [(0, start.plus_seconds(100)), (100, start)] {
+        let err = multisig
+            .approve(TxMsg::TxSelf(SelfTx::CreateStream {
+                recipient: addr("payee"),
+                amount: Coin::new(amount, DENOM),
+                start,
+                end,
+            }))
+            .unwrap_err();
+        assert_eq!(ContractError::InvalidStream, err);
+    }
+
+    let err = multisig
+        .approve(TxMsg::TxSelf(SelfTx::CreateStream {
+            recipient: addr("payee"),
+            amount: Coin::new(WALLET_FUNDS + 1, DENOM),
+            start,
+            end: start.plus_seconds(100),
+        }))
+        .unwrap_err();
+    assert_eq!(
+        ContractError::InsufficientFunds {
+            denom: DENOM.to_string(),
+            available: Uint128::new(WALLET_FUNDS),
+            required: Uint128::new(WALLET_FUNDS + 1)
+        },
+        err
+    );
+}
+
+#[test]
+fn test_module() {
+    let mut multisig = MultisigApp::new();
+
+    assert_eq!(
+        ContractError::Unauthorized {},
+        execute_from_module(&mut multisig, vec![send("recipient", 1)]).unwrap_err()
+    );
+
+    multisig
+        .approve(TxMsg::TxSelf(SelfTx::EnableModule {
+            module: addr("module"),
+        }))
+        .unwrap();
+    let modules: ListModulesResp = multisig
+        .query(&QueryMsg::ListModules {
+            start_after: None,
+            limit: None,
+        })
+        .unwrap();
+    assert_eq!(vec![addr("module")], modules.modules);
+
+    let res = execute_from_module(
+        &mut multisig,
+        vec![send("recipient", 1), send("recipient", 2)],
+    )
+    .unwrap();
+    assert!(has_event(
+        &res,
+        "multisig.module",
+        "execute",
+        &[("msg_count", "2"), ("amount", "3usei")]
+    ));
+    assert_eq!(3, multisig.balance("recipient"));
+
+    multisig
+        .approve(TxMsg::TxSelf(SelfTx::DisableModule {
+            module: addr("module"),
+        }))
+        .unwrap();
+    assert_eq!(
+        ContractError::Unauthorized {},
+        execute_from_module(&mut multisig, vec![send("recipient", 1)]).unwrap_err()
+    );
+    assert_eq!(
+        ContractError::NonExistentModule("module".to_string()),
+        multisig
+            .approve(TxMsg::TxSelf(SelfTx::DisableModule {
+                module: addr("module"),
+            }))
+            .unwrap_err()
+    );
+}
+
+#[test]
+fn test_module_limits() {
+    let mut multisig = MultisigApp::new();
+    multisig
+        .approve(TxMsg::TxSelf(SelfTx::EnableModule {
+            module: addr("module"),
+        }))
+        .unwrap();
+    multisig
+        .approve(TxMsg::TxSelf(SelfTx::SetRateLimit {
+            denom: DENOM.to_string(),
+            limit: Some(RateLimit {
+                amount: Uint128::new(10),
+                window: 100,
+            }),
+        }))
+        .unwrap();
+
+    // Split payments count together
+    assert_eq!(
+        ContractError::RateLimitExceeded {
+            denom: DENOM.to_string(),
+            available: Uint128::new(10),
+            required: Uint128::new(12)
+        },
+        execute_from_module(
+            &mut multisig,
+            vec![send("recipient", 6), send("recipient", 6)]
+        )
+        .unwrap_err()
+    );
+
+    let self_call = WasmMsg::Execute {
+        contract_addr: multisig.wallet.to_string(),
+        msg: to_json_binary(&ExecuteMsg::RunDueSchedules {}).unwrap(),
+        funds: vec![],
+    };
+    assert_eq!(
+        ContractError::SelfCall,
+        execute_from_module(&mut multisig, vec![self_call.into()]).unwrap_err()
+    );
+
+    multisig.execute(OWNERS[0], &ExecuteMsg::Pause {}).unwrap();
+    assert!(matches!(
+        execute_from_module(&mut multisig, vec![send("recipient", 1)]).unwrap_err(),
+        ContractError::Paused { .. }
+    ));
+}
+
+fn assert_unsupported_module_msg(msg: CosmosMsg) {
+    let mut multisig = MultisigApp::new();
+    multisig
+        .approve(TxMsg::TxSelf(SelfTx::EnableModule {
+            module: addr("module"),
+        }))
+        .unwrap();
+
+    assert_eq!(
+        ContractError::UnsupportedModuleMsg,
+        execute_from_module(&mut multisig, vec![send("recipient", 1), msg]).unwrap_err()
+    );
+    assert_eq!(0, multisig.balance("recipient"));
+}
+
+#[test]
+fn test_module_stargate() {
+    assert_unsupported_module_msg(CosmosMsg::Stargate {
+        type_url: "/cosmos.bank.v1beta1.MsgSend".to_string(),
+        value: Binary::default(),
+    });
+}
+
+#[test]
+fn test_module_instantiate() {
+    assert_unsupported_module_msg(
+        WasmMsg::Instantiate {
+            admin: None,
+            code_id: 1,
+            msg: Binary::default(),
+            funds: coins(100, DENOM),
+            label: "drain".to_string(),
+        }
+        .into(),
+    );
+    assert_unsupported_module_msg(
+        WasmMsg::Instantiate2 {
+            admin: None,
+            code_id: 1,
+            label: "drain".to_string(),
+            msg: Binary::default(),
+            funds: coins(100, DENOM),
+            salt: Binary::from(b"salt"),
+        }
+        .into(),
+    );
+}
+
+#[test]
+fn test_module_contract_admin() {
+    assert_unsupported_module_msg(
+        WasmMsg::Migrate {
+            contract_addr: addr("contract").to_string(),
+            new_code_id: 2,
+            msg: Binary::default(),
+        }
+        .into(),
+    );
+    assert_unsupported_module_msg(
+        WasmMsg::UpdateAdmin {
+            contract_addr: addr("contract").to_string(),
+            admin: addr("module").to_string(),
+        }
+        .into(),
+    );
+}
+
+#[test]
+fn test_module_staking() {
+    assert_unsupported_module_msg(
+        StakingMsg::Delegate {
+            validator: "validator".to_string(),
+            amount: Coin::new(100, DENOM),
+        }
+        .into(),
+    );
+    assert_unsupported_module_msg(
+        DistributionMsg::SetWithdrawAddress {
+            address: addr("module").to_string(),
+        }
+        .into(),
+    );
+}
+
+#[test]
+fn test_module_ibc_transfer() {
+    assert_unsupported_module_msg(
+        IbcMsg::Transfer {
+            channel_id: "channel-0".to_string(),
+            to_address: "cosmos1recipient".to_string(),
+            amount: Coin::new(100, DENOM),
+            timeout: Timestamp::from_seconds(1_000_000).into(),
+        }
+        .into(),
+    );
+}
+
+#[test]
+fn test_ibc_transfer() {
+    let mut multisig = MultisigApp::new();
+    let tx_msg = TxMsg::IbcTransfer {
+        channel_id: "channel-0".to_string(),
+        to_address: "osmo1recipient".to_string(),
+        amount: Coin::new(10, DENOM),
+        timeout: TransferTimeout::Time { seconds: 600 },
+    };
+
+    let msg = ExecuteMsg::CreateTransaction {
+        tx_msg: tx_msg.clone(),
+        auto_execute: None,
+    };
+    let res = multisig.execute(OWNERS[0], &msg).unwrap();
+    assert!(has_event(
+        &res,
+        "multisig.tx",
+        "propose",
+        &[
+            ("msg_type", "ibc_transfer"),
+            ("channel_id", "channel-0"),
+            ("timeout", "600s")
+        ]
+    ));
+
+    // multi-test has no IBC, the transfer fails and stays pending
+    let tx_id = multisig.last_tx_id();
+    multisig.sign(OWNERS[1], tx_id).unwrap();
+    let res = multisig.execute_tx(OWNERS[0], tx_id).unwrap();
+    assert!(has_event(&res, "multisig.tx", "execution_failed", &[]));
+
+    // Recipients on other chains are allowlisted as they are
+    multisig
+        .approve(TxMsg::TxSelf(SelfTx::SetAllowlistMode { enabled: true }))
+        .unwrap();
+    assert_eq!(
+        ContractError::RecipientNotAllowed("osmo1recipient".to_string()),
+        multisig.execute_tx(OWNERS[0], tx_id).unwrap_err()
+    );
+}
+
+#[test]
+fn test_authz_and_fee_grants() {
+    let mut multisig = MultisigApp::new();
+
+    // multi-test doesn't support stargate messages, they fail and stay pending
+    for tx_msg in [
+        TxMsg::AuthzGrant {
+            grantee: addr("grantee"),
+            authorization: Authorization::Send {
+                spend_limit: coins(5, DENOM),
+            },
+            expiration: None,
+        },
+        TxMsg::AuthzRevoke {
+            grantee: addr("grantee"),
+            msg_type_url: "/cosmos.bank.v1beta1.MsgSend".to_string(),
+        },
+        TxMsg::GrantAllowance {
+            grantee: addr("grantee"),
+            spend_limit: vec![],
+            expiration: None,
+        },
+        TxMsg::RevokeAllowance {
+            grantee: addr("grantee"),
+        },
+    ] {
+        let res = multisig.approve(tx_msg).unwrap();
+        assert!(has_event(
+            &res,
+            "multisig.tx",
+            "execution_failed",
+            &[("grantee", "grantee")]
+        ));
+    }
+
+    assert_eq!(4, multisig.pending().len());
+    assert!(multisig
+        .pending()
+        .iter()
+        .all(|tx| matches!(tx.status, TxStatus::ExecutionFailed { .. })));
+}
+
+#[test]
+fn test_grant_allowlist() {
+    let mut multisig = MultisigApp::new();
+    multisig
+        .approve(TxMsg::TxSelf(SelfTx::AllowRecipients {
+            addrs: vec![addr("grantee")],
+        }))
+        .unwrap();
+    multisig
+        .approve(TxMsg::TxSelf(SelfTx::SetAllowlistMode { enabled: true }))
+        .unwrap();
+
+    let send_grant = |grantee| TxMsg::AuthzGrant {
+        grantee: addr(grantee),
+        authorization: Authorization::Send {
+            spend_limit: coins(5, DENOM),
+        },
+        expiration: None,
+    };
+    let allowance = |grantee| TxMsg::GrantAllowance {
+        grantee: addr(grantee),
+        spend_limit: coins(5, DENOM),
+        expiration: None,
+    };
+    for tx_msg in [send_grant("stranger"), allowance("stranger")] {
+        assert_eq!(
+            ContractError::RecipientNotAllowed(addr("stranger").to_string()),
+            multisig.approve(tx_msg).unwrap_err()
+        );
+    }
+
+    // Allowlisted grantees pass, multi-test then fails the stargate message
+    for tx_msg in [send_grant("grantee"), allowance("grantee")] {
+        let res = multisig.approve(tx_msg).unwrap();
+        assert!(has_event(&res, "multisig.tx", "execution_failed", &[]));
+    }
+}
+
+#[test]
+fn test_grant_rate_limit() {
+    let mut multisig = MultisigApp::new();
+    multisig
+        .approve(TxMsg::TxSelf(SelfTx::SetRateLimit {
+            denom: DENOM.to_string(),
+            limit: Some(RateLimit {
+                amount: Uint128::new(10),
+                window: 1000,
+            }),
+        }))
+        .unwrap();
+
+    // The whole spend limit counts, the grantee can spend it at once
+    assert_eq!(
+        ContractError::RateLimitExceeded {
+            denom: DENOM.to_string(),
+            available: Uint128::new(10),
+            required: Uint128::new(20)
+        },
+        multisig
+            .approve(TxMsg::AuthzGrant {
+                grantee: addr("grantee"),
+                authorization: Authorization::Send {
+                    spend_limit: coins(20, DENOM),
+                },
+                expiration: None,
+            })
+            .unwrap_err()
+    );
+    assert_eq!(
+        ContractError::RateLimitExceeded {
+            denom: DENOM.to_string(),
+            available: Uint128::new(10),
+            required: Uint128::new(20)
+        },
+        multisig
+            .approve(TxMsg::GrantAllowance {
+                grantee: addr("grantee"),
+                spend_limit: coins(20, DENOM),
+                expiration: None,
+            })
+            .unwrap_err()
+    );
+
+    // An unlimited grant would get around the rate limit
+    for tx_msg in [
+        TxMsg::AuthzGrant {
+            grantee: addr("grantee"),
+            authorization: Authorization::Send {
+                spend_limit: vec![],
+            },
+            expiration: None,
+        },
+        TxMsg::GrantAllowance {
+            grantee: addr("grantee"),
+            spend_limit: vec![],
+            expiration: None,
+        },
+    ] {
+        assert_eq!(
+            ContractError::UnlimitedGrant,
+            multisig.approve(tx_msg).unwrap_err()
+        );
+    }
+}
+
+#[test]
+fn test_grant_rate_limit_removed() {
+    let mut msg = instantiate_msg();
+    msg.rate_limit_delay = Some(100);
+    let mut multisig = MultisigApp::with_msg(msg);
+    for limit in [
+        Some(RateLimit {
+            amount: Uint128::new(10),
+            window: 1000,
+        }),
+        None,
+    ] {
+        multisig
+            .approve(TxMsg::TxSelf(SelfTx::SetRateLimit {
+                denom: DENOM.to_string(),
+                limit,
+            }))
+            .unwrap();
+    }
+    let unlimited = TxMsg::GrantAllowance {
+        grantee: addr("grantee"),
+        spend_limit: vec![],
+        expiration: None,
+    };
+
+    // The limit holds until its removal takes effect
+    assert_eq!(
+        ContractError::UnlimitedGrant,
+        multisig.approve(unlimited.clone()).unwrap_err()
+    );
+    multisig.advance(100);
+    let res = multisig.approve(unlimited).unwrap();
+    assert!(has_event(&res, "multisig.tx", "execution_failed", &[]));
+}
+
+#[test]
+fn test_generic_grant() {
+    let mut multisig = MultisigApp::new();
+    let generic = |msg_type_url: &str| TxMsg::AuthzGrant {
+        grantee: addr("grantee"),
+        authorization: Authorization::Generic {
+            msg_type_url: msg_type_url.to_string(),
+        },
+        expiration: None,
+    };
+    let send = "/cosmos.bank.v1beta1.MsgSend";
+
+    // Unrestricted wallets can grant anything, multi-test then fails the stargate message
+    let res = multisig.approve(generic(send)).unwrap();
+    assert!(has_event(&res, "multisig.tx", "execution_failed", &[]));
+
+    multisig
+        .approve(TxMsg::TxSelf(SelfTx::SetRateLimit {
+            denom: DENOM.to_string(),
+            limit: Some(RateLimit {
+                amount: Uint128::new(10),
+                window: 1000,
+            }),
+        }))
+        .unwrap();
+    for msg_type_url in [
+        send,
+        "/cosmos.bank.v1beta1.MsgMultiSend",
+        "/ibc.applications.transfer.v1.MsgTransfer",
+        "/cosmwasm.wasm.v1.MsgExecuteContract",
+    ] {
+        assert_eq!(
+            ContractError::UnrestrictedGrant(msg_type_url.to_string()),
+            multisig.approve(generic(msg_type_url)).unwrap_err()
+        );
+    }
+
+    // Grants that can't move funds stay allowed
+    let res = multisig
+        .approve(generic("/cosmos.gov.v1beta1.MsgVote"))
+        .unwrap();
+    assert!(has_event(&res, "multisig.tx", "execution_failed", &[]));
+
+    // The allowlist mode alone restricts them as well
+    let mut multisig = MultisigApp::new();
+    multisig
+        .approve(TxMsg::TxSelf(SelfTx::SetAllowlistMode { enabled: true }))
+        .unwrap();
+    assert_eq!(
+        ContractError::UnrestrictedGrant(send.to_string()),
+        multisig.approve(generic(send)).unwrap_err()
+    );
+}
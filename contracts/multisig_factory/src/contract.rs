@@ -4,6 +4,7 @@ use cosmwasm_std::{
     to_json_binary, Addr, Binary, Deps, DepsMut, Env, MessageInfo, QueryRequest, Reply, Response,
     StdError, StdResult, SubMsg, WasmMsg, WasmQuery,
 };
+use multisig::events::{event, FACTORY_EVENT};
 use multisig::msg::InstantiateMsg as MultiSigInstantiateMsg;
 
 use cw2::set_contract_version;
@@ -37,9 +38,11 @@ pub fn instantiate(
         NFT_ADDRESS.save(deps.storage, &addr)?;
     }
 
-    Ok(Response::new()
-        .add_attribute("method", "instantiate")
-        .add_attribute("creator", info.sender))
+    Ok(Response::new().add_event(
+        event(FACTORY_EVENT, "instantiate")
+            .add_attribute("actor", info.sender)
+            .add_attribute("code_id", msg.multisig_code_id.to_string()),
+    ))
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
@@ -70,9 +73,11 @@ pub fn execute_update_code_id(
 ) -> Result<Response, ContractError> {
     cw_ownable::assert_owner(deps.storage, &info.sender)?;
     MULTISIG_CODE_ID.save(deps.storage, &multisig_code_id)?;
-    Ok(Response::default()
-        .add_attribute("action", "update_code_id")
-        .add_attribute("multisig_code_id", multisig_code_id.to_string()))
+    Ok(Response::default().add_event(
+        event(FACTORY_EVENT, "update_code_id")
+            .add_attribute("actor", info.sender)
+            .add_attribute("code_id", multisig_code_id.to_string()),
+    ))
 }
 
 pub fn execute_update_nft_address(
@@ -82,9 +87,11 @@ pub fn execute_update_nft_address(
 ) -> Result<Response, ContractError> {
     cw_ownable::assert_owner(deps.storage, &info.sender)?;
     NFT_ADDRESS.save(deps.storage, &cw721_address)?;
-    Ok(Response::default()
-        .add_attribute("action", "update_code_id")
-        .add_attribute("nft_contract_address", cw721_address.to_string()))
+    Ok(Response::default().add_event(
+        event(FACTORY_EVENT, "update_nft_address")
+            .add_attribute("actor", info.sender)
+            .add_attribute("nft_address", cw721_address),
+    ))
 }
 
 pub fn execute_instantiate_multisig_contract(
@@ -116,7 +123,7 @@ pub fn instantiate_contract(
             })?,
         }))?;
 
-    if cw721_query_result.tokens.len() < 1 {
+    if cw721_query_result.tokens.is_empty() {
         return Err(ContractError::NotNftHolder {});
     }
 
@@ -126,7 +133,7 @@ pub fn instantiate_contract(
         code_id,
         msg: to_json_binary(&instantiate_msg)?,
         funds: vec![],
-        label,
+        label: label.clone(),
     };
 
     TEMP_WALLET_OWNER.save(deps.storage, &sender)?;
@@ -134,7 +141,12 @@ pub fn instantiate_contract(
     let msg = SubMsg::reply_on_success(instantiate, INSTANTIATE_CONTRACT_REPLY_ID);
 
     Ok(Response::default()
-        .add_attribute("action", "instantiate_cw_multisig")
+        .add_event(
+            event(FACTORY_EVENT, "create_wallet")
+                .add_attribute("actor", sender)
+                .add_attribute("code_id", code_id.to_string())
+                .add_attribute("label", label),
+        )
         .add_submessage(msg))
 }
 
@@ -143,13 +155,13 @@ pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> Result<Response, ContractE
     match msg.id {
         INSTANTIATE_CONTRACT_REPLY_ID => {
             let result = msg.result.into_result().map_err(StdError::generic_err)?;
-            let event = result
+            let instantiate_event = result
                 .events
                 .iter()
                 .find(|event| event.ty == "instantiate")
                 .ok_or_else(|| StdError::generic_err("cannot find `instantiate_contract` event"))?;
 
-            let contract_address = &event
+            let contract_address = &instantiate_event
                 .attributes
                 .iter()
                 .find(|attr| attr.key == "_contract_address")
@@ -172,11 +184,13 @@ pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> Result<Response, ContractE
             // let owner_wallets = MULTISIG_WALLET_MAP.load(deps.storage, owner);
             owner_wallets.push(contract_address.clone());
 
-            MULTISIG_WALLET_MAP.save(deps.storage, owner, &owner_wallets)?;
+            MULTISIG_WALLET_MAP.save(deps.storage, owner.clone(), &owner_wallets)?;
 
-            Ok(Response::new()
-                .add_attribute("method", "handle_instantiate_reply")
-                .add_attribute("contract_address", contract_address))
+            Ok(Response::new().add_event(
+                event(FACTORY_EVENT, "wallet_created")
+                    .add_attribute("wallet", contract_address)
+                    .add_attribute("owner", owner),
+            ))
         }
         _ => Err(ContractError::UnknownReplyID {}),
     }
@@ -34,9 +34,9 @@ pub enum ExecuteMsg {
 #[cw_serde]
 #[derive(QueryResponses)]
 pub enum QueryMsg {
-    #[returns(cosmwasm_std::Binary)]
+    #[returns(MultisigWallets)]
     GetAllMultisigWallets {},
 
-    #[returns(cosmwasm_std::Binary)]
+    #[returns(MultisigWallets)]
     GetMultisigWalletsByOwner { owner: Addr },
 }
@@ -0,0 +1,342 @@
+use cosmwasm_std::testing::{mock_dependencies, mock_env};
+use cosmwasm_std::{Addr, Empty, Reply, StdError, SubMsgResult};
+use cw_multi_test::{App, AppResponse, Contract, ContractWrapper, Executor};
+use cw_ownable::OwnershipError;
+use multisig::msg::InstantiateMsg as MultiSigInstantiateMsg;
+use multisig_factory::contract::{execute, instantiate, query, reply};
+use multisig_factory::error::ContractError;
+use multisig_factory::msg::{ExecuteMsg, InstantiateMsg, MultisigWallets, QueryMsg};
+
+const OWNER: &str = "owner";
+const HOLDER: &str = "holder";
+
+fn addr(addr: &str) -> Addr {
+    Addr::unchecked(addr)
+}
+
+fn factory_contract() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new(execute, instantiate, query).with_reply(reply))
+}
+
+fn multisig_contract() -> Box<dyn Contract<Empty>> {
+    Box::new(
+        ContractWrapper::new(
+            multisig::contract::execute,
+            multisig::contract::instantiate,
+            multisig::contract::query,
+        )
+        .with_reply(multisig::contract::reply),
+    )
+}
+
+/// 2 of 3 wallet owned by the holder and two other owners
+fn wallet_msg() -> MultiSigInstantiateMsg {
+    MultiSigInstantiateMsg {
+        owners: vec![addr(HOLDER), addr("owner2"), addr("owner3")],
+        quorum: 2,
+        group: None,
+        guardians: None,
+        pause_duration: None,
+        permissionless_execution: None,
+        auto_execute: None,
+        rate_limit_delay: None,
+        allowlist: None,
+    }
+}
+
+/// cw721 answering the `Tokens` query of the factory's gating from a fixed list of holders
+mod mock_cw721 {
+    use cosmwasm_schema::cw_serde;
+    use cosmwasm_std::{
+        to_json_binary, Binary, Deps, DepsMut, Empty, Env, MessageInfo, Response, StdError,
+        StdResult,
+    };
+    use cw721::{Cw721QueryMsg, TokensResponse};
+    use cw_storage_plus::Map;
+
+    const TOKENS: Map<&str, Vec<String>> = Map::new("tokens");
+
+    #[cw_serde]
+    pub struct InstantiateMsg {
+        pub holders: Vec<String>,
+    }
+
+    pub fn instantiate(
+        deps: DepsMut,
+        _env: Env,
+        _info: MessageInfo,
+        msg: InstantiateMsg,
+    ) -> StdResult<Response> {
+        for (id, holder) in msg.holders.iter().enumerate() {
+            TOKENS.save(deps.storage, holder, &vec![id.to_string()])?;
+        }
+        Ok(Response::new())
+    }
+
+    pub fn execute(
+        _deps: DepsMut,
+        _env: Env,
+        _info: MessageInfo,
+        _msg: Empty,
+    ) -> StdResult<Response> {
+        Err(StdError::generic_err("not supported"))
+    }
+
+    pub fn query(deps: Deps, _env: Env, msg: Cw721QueryMsg) -> StdResult<Binary> {
+        match msg {
+            Cw721QueryMsg::Tokens { owner, .. } => to_json_binary(&TokensResponse {
+                tokens: TOKENS.may_load(deps.storage, &owner)?.unwrap_or_default(),
+            }),
+            _ => Err(StdError::generic_err("not supported")),
+        }
+    }
+
+    pub fn contract() -> Box<dyn cw_multi_test::Contract<Empty>> {
+        Box::new(cw_multi_test::ContractWrapper::new(
+            execute,
+            instantiate,
+            query,
+        ))
+    }
+}
+
+/// Factory deployed on a multi-test chain next to a cw721 held by `HOLDER`, with typed
+/// wrappers around its messages. Execute errors are the factory's `ContractError`.
+struct FactoryApp {
+    app: App,
+    multisig_code_id: u64,
+    nft: Addr,
+    factory: Addr,
+}
+
+impl FactoryApp {
+    fn new() -> Self {
+        Self::with_nft(true)
+    }
+
+    /// Factory gated by the cw721 unless `gated` is false
+    fn with_nft(gated: bool) -> Self {
+        let mut app = App::default();
+        let multisig_code_id = app.store_code(multisig_contract());
+        let nft_code_id = app.store_code(mock_cw721::contract());
+        let factory_code_id = app.store_code(factory_contract());
+
+        let nft = app
+            .instantiate_contract(
+                nft_code_id,
+                addr(OWNER),
+                &mock_cw721::InstantiateMsg {
+                    holders: vec![HOLDER.to_string()],
+                },
+                &[],
+                "NFT",
+                None,
+            )
+            .unwrap();
+        let factory = app
+            .instantiate_contract(
+                factory_code_id,
+                addr(OWNER),
+                &InstantiateMsg {
+                    owner: Some(OWNER.to_string()),
+                    multisig_code_id,
+                    cw721_address: gated.then(|| nft.clone()),
+                },
+                &[],
+                "Factory",
+                None,
+            )
+            .unwrap();
+
+        Self {
+            app,
+            multisig_code_id,
+            nft,
+            factory,
+        }
+    }
+
+    fn execute(&mut self, sender: &str, msg: &ExecuteMsg) -> Result<AppResponse, ContractError> {
+        self.app
+            .execute_contract(addr(sender), self.factory.clone(), msg, &[])
+            .map_err(|err| err.downcast().unwrap())
+    }
+
+    fn create_wallet(&mut self, sender: &str) -> Result<AppResponse, ContractError> {
+        self.execute(
+            sender,
+            &ExecuteMsg::InstantiateMultiSigContract {
+                instantiate_msg: wallet_msg(),
+                label: "Wallet".to_string(),
+            },
+        )
+    }
+
+    fn wallets(&self) -> Vec<String> {
+        let resp: MultisigWallets = self
+            .app
+            .wrap()
+            .query_wasm_smart(&self.factory, &QueryMsg::GetAllMultisigWallets {})
+            .unwrap();
+        resp.wallets
+    }
+
+    fn wallets_of(&self, owner: &str) -> Vec<String> {
+        let resp: MultisigWallets = self
+            .app
+            .wrap()
+            .query_wasm_smart(
+                &self.factory,
+                &QueryMsg::GetMultisigWalletsByOwner { owner: addr(owner) },
+            )
+            .unwrap();
+        resp.wallets
+    }
+}
+
+fn has_event(res: &AppResponse, action: &str, attributes: &[(&str, &str)]) -> bool {
+    res.events.iter().any(|event| {
+        event.ty == "wasm-multisig.factory"
+            && [("action", action)]
+                .iter()
+                .chain(attributes)
+                .all(|(key, value)| {
+                    event
+                        .attributes
+                        .iter()
+                        .any(|attr| attr.key == *key && attr.value == *value)
+                })
+    })
+}
+
+#[test]
+fn test_instantiate() {
+    let factory = FactoryApp::new();
+    assert!(factory.wallets().is_empty());
+    assert!(factory.wallets_of(HOLDER).is_empty());
+}
+
+#[test]
+fn test_create_wallet() {
+    let mut factory = FactoryApp::new();
+    let res = factory.create_wallet(HOLDER).unwrap();
+
+    let wallets = factory.wallets();
+    assert_eq!(1, wallets.len());
+    assert_eq!(wallets, factory.wallets_of(HOLDER));
+    assert!(factory.wallets_of("owner2").is_empty());
+    assert!(has_event(
+        &res,
+        "wallet_created",
+        &[("wallet", &wallets[0]), ("owner", HOLDER)]
+    ));
+
+    // The creator administers the wallet, which keeps the owners it was given
+    let info = factory
+        .app
+        .wrap()
+        .query_wasm_contract_info(&wallets[0])
+        .unwrap();
+    assert_eq!(factory.multisig_code_id, info.code_id);
+    assert_eq!(Some(HOLDER.to_string()), info.admin);
+    let admins: multisig::msg::ListAdminsResp = factory
+        .app
+        .wrap()
+        .query_wasm_smart(&wallets[0], &multisig::msg::QueryMsg::ListAdmins {})
+        .unwrap();
+    assert_eq!(wallet_msg().owners, admins.admins);
+
+    factory.create_wallet(HOLDER).unwrap();
+    assert_eq!(2, factory.wallets_of(HOLDER).len());
+}
+
+#[test]
+fn test_create_wallet_invalid() {
+    let mut factory = FactoryApp::new();
+    assert_eq!(
+        ContractError::NotNftHolder {},
+        factory.create_wallet("stranger").unwrap_err()
+    );
+
+    // A wallet failing to instantiate isn't recorded
+    let msg = ExecuteMsg::InstantiateMultiSigContract {
+        instantiate_msg: MultiSigInstantiateMsg {
+            quorum: 4,
+            ..wallet_msg()
+        },
+        label: "Wallet".to_string(),
+    };
+    let factory_addr = factory.factory.clone();
+    factory
+        .app
+        .execute_contract(addr(HOLDER), factory_addr, &msg, &[])
+        .unwrap_err();
+    assert!(factory.wallets().is_empty());
+
+    let mut factory = FactoryApp::with_nft(false);
+    assert!(matches!(
+        factory.create_wallet(HOLDER).unwrap_err(),
+        ContractError::Std(StdError::NotFound { .. })
+    ));
+}
+
+#[test]
+fn test_update_code_id() {
+    let mut factory = FactoryApp::new();
+    let msg = ExecuteMsg::UpdateCodeId {
+        multisig_code_id: 42,
+    };
+    assert_eq!(
+        ContractError::Ownable(OwnershipError::NotOwner),
+        factory.execute(HOLDER, &msg).unwrap_err()
+    );
+
+    let res = factory.execute(OWNER, &msg).unwrap();
+    assert!(has_event(&res, "update_code_id", &[("code_id", "42")]));
+    // No code is stored under the new id
+    factory
+        .app
+        .execute_contract(
+            addr(HOLDER),
+            factory.factory.clone(),
+            &ExecuteMsg::InstantiateMultiSigContract {
+                instantiate_msg: wallet_msg(),
+                label: "Wallet".to_string(),
+            },
+            &[],
+        )
+        .unwrap_err();
+}
+
+#[test]
+fn test_update_nft_address() {
+    let mut factory = FactoryApp::with_nft(false);
+    let msg = ExecuteMsg::UpdateNFTAddress {
+        cw721_address: factory.nft.clone(),
+    };
+    assert_eq!(
+        ContractError::Ownable(OwnershipError::NotOwner),
+        factory.execute(HOLDER, &msg).unwrap_err()
+    );
+
+    let res = factory.execute(OWNER, &msg).unwrap();
+    assert!(has_event(
+        &res,
+        "update_nft_address",
+        &[("nft_address", factory.nft.as_str())]
+    ));
+    factory.create_wallet(HOLDER).unwrap();
+    assert_eq!(1, factory.wallets().len());
+}
+
+#[test]
+fn test_unknown_reply_id() {
+    let reply_msg = Reply {
+        id: 99,
+        result: SubMsgResult::Err("error".to_string()),
+    };
+    assert_eq!(
+        ContractError::UnknownReplyID {},
+        reply(mock_dependencies().as_mut(), mock_env(), reply_msg).unwrap_err()
+    );
+}
@@ -0,0 +1,277 @@
+//! Builds validated messages from command-line flags
+
+use anyhow::{anyhow, bail, ensure, Context, Result};
+use clap::{Subcommand, ValueEnum};
+use cosmwasm_std::{from_json, Addr, Coin, Uint128, VoteOption};
+use multisig::msg::QueryMsg;
+use multisig::state::{SelfTx, TransferTimeout, TxMsg};
+
+#[derive(Subcommand)]
+pub enum TxArgs {
+    /// Sends coins to an address on this chain
+    Bank {
+        #[arg(long)]
+        to: String,
+        /// Coins in the SDK format, e.g. `10usei,5uatom`
+        #[arg(long)]
+        amount: String,
+    },
+    /// Sends a coin over IBC
+    IbcTransfer {
+        #[arg(long)]
+        channel_id: String,
+        /// Address on the destination chain
+        #[arg(long)]
+        to: String,
+        #[arg(long)]
+        amount: String,
+        /// Seconds after the execution the transfer times out
+        #[arg(long, default_value_t = 600)]
+        timeout_seconds: u64,
+    },
+    /// Votes on a chain governance proposal
+    GovVote {
+        #[arg(long)]
+        proposal_id: u64,
+        #[arg(long)]
+        vote: Vote,
+    },
+    AddOwner {
+        #[arg(long)]
+        owner: String,
+        #[arg(long)]
+        quorum: Option<u32>,
+    },
+    RemoveOwner {
+        #[arg(long)]
+        owner: String,
+        #[arg(long)]
+        quorum: Option<u32>,
+    },
+    UpdateQuorum {
+        #[arg(long)]
+        quorum: u32,
+    },
+    Unpause,
+    /// Any `TxMsg` given as JSON, e.g. `{"tx_self":{"set_auto_execute":{"enabled":true}}}`
+    Json {
+        tx_msg: String,
+    },
+}
+
+#[derive(Clone, ValueEnum)]
+pub enum Vote {
+    Yes,
+    No,
+    Abstain,
+    NoWithVeto,
+}
+
+impl From<Vote> for VoteOption {
+    fn from(vote: Vote) -> Self {
+        match vote {
+            Vote::Yes => VoteOption::Yes,
+            Vote::No => VoteOption::No,
+            Vote::Abstain => VoteOption::Abstain,
+            Vote::NoWithVeto => VoteOption::NoWithVeto,
+        }
+    }
+}
+
+impl TxArgs {
+    pub fn into_tx_msg(self, prefix: &str) -> Result<TxMsg> {
+        let tx_msg = match self {
+            TxArgs::Bank { to, amount } => TxMsg::TxBank {
+                to: validate_addr(&to, prefix)?,
+                coins: parse_coins(&amount)?,
+            },
+            TxArgs::IbcTransfer {
+                channel_id,
+                to,
+                amount,
+                timeout_seconds,
+            } => {
+                let [amount] = <[Coin; 1]>::try_from(parse_coins(&amount)?)
+                    .map_err(|_| anyhow!("an IBC transfer sends a single coin"))?;
+                ensure!(
+                    channel_id.starts_with("channel-"),
+                    "invalid channel id: {channel_id}"
+                );
+                TxMsg::IbcTransfer {
+                    channel_id,
+                    to_address: to,
+                    amount,
+                    timeout: TransferTimeout::Time {
+                        seconds: timeout_seconds,
+                    },
+                }
+            }
+            TxArgs::GovVote { proposal_id, vote } => TxMsg::GovVote {
+                proposal_id,
+                vote: vote.into(),
+            },
+            TxArgs::AddOwner { owner, quorum } => TxMsg::TxSelf(SelfTx::AddOwner {
+                owner: validate_addr(&owner, prefix)?,
+                quorum,
+            }),
+            TxArgs::RemoveOwner { owner, quorum } => TxMsg::TxSelf(SelfTx::RemoveOwner {
+                owner: validate_addr(&owner, prefix)?,
+                quorum,
+            }),
+            TxArgs::UpdateQuorum { quorum } => TxMsg::TxSelf(SelfTx::UpdateQuorum { quorum }),
+            TxArgs::Unpause => TxMsg::TxSelf(SelfTx::Unpause {}),
+            TxArgs::Json { tx_msg } => from_json(tx_msg.as_bytes()).context("invalid TxMsg")?,
+        };
+        Ok(tx_msg)
+    }
+}
+
+#[derive(Subcommand)]
+pub enum QueryArgs {
+    ListPending,
+    ListCompleted {
+        #[arg(long)]
+        offset: Option<u32>,
+        #[arg(long)]
+        limit: Option<u32>,
+    },
+    ListAdmins,
+    ListSigned {
+        #[arg(long)]
+        admin: String,
+        #[arg(long)]
+        tx_id: u128,
+    },
+    /// Signatures a transaction needs, the wallet's quorum without `--tx-id`
+    Quorum {
+        #[arg(long)]
+        tx_id: Option<u128>,
+    },
+    Config,
+    Proposal {
+        #[arg(long)]
+        proposal_id: u64,
+    },
+    /// Any `QueryMsg` given as JSON
+    Json {
+        query_msg: String,
+    },
+}
+
+impl QueryArgs {
+    pub fn into_query_msg(self, prefix: &str) -> Result<QueryMsg> {
+        let query_msg = match self {
+            QueryArgs::ListPending => QueryMsg::ListPending {},
+            QueryArgs::ListCompleted { offset, limit } => {
+                QueryMsg::ListCompletedTxs { offset, limit }
+            }
+            QueryArgs::ListAdmins => QueryMsg::ListAdmins {},
+            QueryArgs::ListSigned { admin, tx_id } => QueryMsg::ListSigned {
+                admin: validate_addr(&admin, prefix)?,
+                tx_id,
+            },
+            QueryArgs::Quorum { tx_id } => QueryMsg::GetQuorum { tx_id },
+            QueryArgs::Config => QueryMsg::GetConfig {},
+            QueryArgs::Proposal { proposal_id } => QueryMsg::Proposal { proposal_id },
+            QueryArgs::Json { query_msg } => {
+                from_json(query_msg.as_bytes()).context("invalid QueryMsg")?
+            }
+        };
+        Ok(query_msg)
+    }
+}
+
+/// Checks that `addr` is a bech32 address of the chain using `prefix`
+pub fn validate_addr(addr: &str, prefix: &str) -> Result<Addr> {
+    let (hrp, _, _) = bech32::decode(addr).with_context(|| format!("invalid address: {addr}"))?;
+    ensure!(
+        hrp == prefix,
+        "address {addr} doesn't have the {prefix} prefix"
+    );
+    Ok(Addr::unchecked(addr))
+}
+
+/// Parses coins in the SDK format, e.g. `10usei,5uatom`
+pub fn parse_coins(coins: &str) -> Result<Vec<Coin>> {
+    coins
+        .split(',')
+        .map(|coin| {
+            let coin = coin.trim();
+            let split = coin
+                .find(|c: char| !c.is_ascii_digit())
+                .ok_or_else(|| anyhow!("coin {coin} has no denom"))?;
+            let (amount, denom) = coin.split_at(split);
+            if amount.is_empty() {
+                bail!("coin {coin} has no amount");
+            }
+            let amount: Uint128 = amount.parse()?;
+            ensure!(!amount.is_zero(), "coin {coin} has a zero amount");
+            Ok(Coin::new(amount.u128(), denom))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ADDR: &str = "sei1qypqxpq9qcrsszg2pvxq6rs0zqg3yyc5jwagqa";
+
+    #[test]
+    fn parses_coins() {
+        assert_eq!(
+            vec![Coin::new(10, "usei"), Coin::new(5, "ibc/27394FB0")],
+            parse_coins("10usei, 5ibc/27394FB0").unwrap()
+        );
+        assert!(parse_coins("usei").is_err());
+        assert!(parse_coins("10").is_err());
+        assert!(parse_coins("0usei").is_err());
+        assert!(parse_coins("").is_err());
+    }
+
+    #[test]
+    fn validates_addresses() {
+        assert_eq!(Addr::unchecked(ADDR), validate_addr(ADDR, "sei").unwrap());
+        assert!(validate_addr(ADDR, "cosmos").is_err());
+        // Changed last character, the checksum doesn't match
+        assert!(validate_addr("sei1qypqxpq9qcrsszg2pvxq6rs0zqg3yyc5jwagqq", "sei").is_err());
+    }
+
+    #[test]
+    fn builds_tx_msgs() {
+        let tx_msg = TxArgs::Bank {
+            to: ADDR.to_string(),
+            amount: "10usei".to_string(),
+        }
+        .into_tx_msg("sei")
+        .unwrap();
+        assert_eq!(
+            TxMsg::TxBank {
+                to: Addr::unchecked(ADDR),
+                coins: vec![Coin::new(10, "usei")],
+            },
+            tx_msg
+        );
+
+        let ibc = TxArgs::IbcTransfer {
+            channel_id: "channel-0".to_string(),
+            to: "cosmos1abc".to_string(),
+            amount: "10usei,5uatom".to_string(),
+            timeout_seconds: 600,
+        };
+        assert!(ibc.into_tx_msg("sei").is_err());
+
+        let json = TxArgs::Json {
+            tx_msg: r#"{"tx_self":{"set_auto_execute":{"enabled":true}}}"#.to_string(),
+        };
+        assert_eq!(
+            TxMsg::TxSelf(SelfTx::SetAutoExecute { enabled: true }),
+            json.into_tx_msg("sei").unwrap()
+        );
+
+        let json = TxArgs::Json {
+            tx_msg: r#"{"tx_self":{"set_auto_execute":{"enable":true}}}"#.to_string(),
+        };
+        assert!(json.into_tx_msg("sei").is_err());
+    }
+}
@@ -0,0 +1,190 @@
+//! Decodes the wallet's events from a tx result
+
+use std::fmt;
+
+use anyhow::{bail, Result};
+use cosmwasm_std::Binary;
+use serde_json::Value;
+
+/// Custom events get this prefix on chain
+const WASM_PREFIX: &str = "wasm-";
+/// Attributes every wasm event has, they aren't printed
+const SKIPPED: [&str; 3] = ["_contract_address", "action", "msg_index"];
+
+pub struct WalletEvent {
+    pub ty: String,
+    pub contract: Option<String>,
+    pub attributes: Vec<(String, String)>,
+}
+
+impl WalletEvent {
+    fn attribute(&self, key: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+impl fmt::Display for WalletEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.ty, self.attribute("action").unwrap_or("?"))?;
+        if let Some(contract) = &self.contract {
+            write!(f, " ({contract})")?;
+        }
+        for (key, value) in &self.attributes {
+            if !SKIPPED.contains(&key.as_str()) {
+                write!(f, "\n  {key}: {value}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// `multisig.*` events of `tx_result`, the output of `seid q tx` or of the RPC `tx` endpoint
+pub fn wallet_events(tx_result: &Value) -> Result<Vec<WalletEvent>> {
+    let events = raw_events(tx_result);
+    if events.is_empty() {
+        bail!("the tx result has no events");
+    }
+
+    Ok(events
+        .into_iter()
+        .filter_map(|event| {
+            let ty = event["type"].as_str()?.strip_prefix(WASM_PREFIX)?;
+            if !ty.starts_with("multisig.") {
+                return None;
+            }
+            let attributes = attributes(event);
+            let contract = attributes
+                .iter()
+                .find(|(key, _)| key == "_contract_address")
+                .map(|(_, value)| value.clone());
+            Some(WalletEvent {
+                ty: ty.to_string(),
+                contract,
+                attributes,
+            })
+        })
+        .collect())
+}
+
+/// Events of the messages' logs if there are any, they are decoded and don't repeat the
+/// ante handler's events. Otherwise the events of the whole transaction.
+fn raw_events(tx_result: &Value) -> Vec<&Value> {
+    let root = [&tx_result["tx_response"], &tx_result["result"]["tx_result"]]
+        .into_iter()
+        .find(|candidate| candidate.is_object())
+        .unwrap_or(tx_result);
+
+    let from_logs: Vec<&Value> = root["logs"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .flat_map(|log| log["events"].as_array().into_iter().flatten())
+        .collect();
+    if !from_logs.is_empty() {
+        return from_logs;
+    }
+    root["events"].as_array().into_iter().flatten().collect()
+}
+
+/// Older nodes base64-encode the attributes of the transaction's events, every wasm event
+/// has an `action` attribute so its absence tells them apart
+fn attributes(event: &Value) -> Vec<(String, String)> {
+    let raw: Vec<(&str, &str)> = event["attributes"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .map(|attr| {
+            (
+                attr["key"].as_str().unwrap_or_default(),
+                attr["value"].as_str().unwrap_or_default(),
+            )
+        })
+        .collect();
+
+    let encoded = !raw.iter().any(|(key, _)| *key == "action");
+    raw.into_iter()
+        .map(|(key, value)| {
+            if encoded {
+                (decode_base64(key), decode_base64(value))
+            } else {
+                (key.to_string(), value.to_string())
+            }
+        })
+        .collect()
+}
+
+fn decode_base64(value: &str) -> String {
+    Binary::from_base64(value)
+        .ok()
+        .and_then(|bytes| String::from_utf8(bytes.0).ok())
+        .unwrap_or_else(|| value.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn attr(key: &str, value: &str) -> Value {
+        serde_json::json!({ "key": key, "value": value })
+    }
+
+    fn b64(value: &str) -> String {
+        Binary::from(value.as_bytes()).to_base64()
+    }
+
+    #[test]
+    fn decodes_logs() {
+        let tx_result = serde_json::json!({
+            "tx_response": {
+                "logs": [{
+                    "events": [
+                        { "type": "message", "attributes": [attr("action", "/cosmwasm.wasm.v1.MsgExecuteContract")] },
+                        { "type": "wasm-multisig.tx", "attributes": [
+                            attr("_contract_address", "sei1wallet"),
+                            attr("action", "propose"),
+                            attr("tx_id", "3"),
+                            attr("actor", "sei1owner"),
+                        ]},
+                    ]
+                }],
+                "events": [{ "type": "wasm-multisig.tx", "attributes": [attr("action", "propose")] }]
+            }
+        });
+
+        let events = wallet_events(&tx_result).unwrap();
+        assert_eq!(1, events.len());
+        assert_eq!(
+            "multisig.tx propose (sei1wallet)\n  tx_id: 3\n  actor: sei1owner",
+            events[0].to_string()
+        );
+    }
+
+    #[test]
+    fn decodes_base64_events() {
+        let tx_result = serde_json::json!({
+            "result": {
+                "tx_result": {
+                    "events": [{ "type": "wasm-multisig.owner", "attributes": [
+                        attr(&b64("_contract_address"), &b64("sei1wallet")),
+                        attr(&b64("action"), &b64("add")),
+                        attr(&b64("owner"), &b64("sei1new")),
+                    ]}]
+                }
+            }
+        });
+
+        let events = wallet_events(&tx_result).unwrap();
+        assert_eq!(
+            "multisig.owner add (sei1wallet)\n  owner: sei1new",
+            events[0].to_string()
+        );
+    }
+
+    #[test]
+    fn rejects_results_without_events() {
+        assert!(wallet_events(&serde_json::json!({ "code": 5 })).is_err());
+    }
+}
@@ -0,0 +1,76 @@
+//! Proposal hash, the hex SHA-256 of the `TxMsg` serialized as JSON. It's a convention of
+//! this tool, the wallet never computes it: signers hash the pending transaction returned
+//! by the wallet's queries and compare it with the hash of the message they reviewed.
+
+use anyhow::{bail, Result};
+use cosmwasm_std::{from_json, to_json_vec, HexBinary};
+use multisig::msg::ExecuteMsg;
+use multisig::state::{Transaction, TxMsg};
+use sha2::{Digest, Sha256};
+
+pub fn proposal_hash(tx_msg: &TxMsg) -> Result<String> {
+    let hash = Sha256::digest(to_json_vec(tx_msg)?);
+    Ok(HexBinary::from(hash.as_slice()).to_hex())
+}
+
+/// Hash of the transaction proposed by `json`, a `TxMsg`, a `create_transaction` or
+/// `propose` message, or a transaction from the wallet's queries
+pub fn proposal_hash_of(json: &str) -> Result<String> {
+    proposal_hash(&tx_msg_of(json)?)
+}
+
+fn tx_msg_of(json: &str) -> Result<TxMsg> {
+    let json = json.trim().as_bytes();
+    if let Ok(tx_msg) = from_json::<TxMsg>(json) {
+        return Ok(tx_msg);
+    }
+    if let Ok(tx) = from_json::<Transaction>(json) {
+        return Ok(tx.tx_msg);
+    }
+    match from_json::<ExecuteMsg>(json) {
+        Ok(ExecuteMsg::CreateTransaction { tx_msg, .. }) => Ok(tx_msg),
+        Ok(ExecuteMsg::Propose { msgs, .. }) => Ok(TxMsg::Messages { msgs }),
+        Ok(_) => bail!("the message doesn't propose a transaction"),
+        Err(_) => bail!("expected a TxMsg, a proposing message or a transaction"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TX_MSG: &str = r#"{"tx_bank":{"to":"rcpt","coins":[{"denom":"usei","amount":"10"}]}}"#;
+
+    #[test]
+    fn hashes_the_serialized_tx_msg() {
+        let hash = proposal_hash_of(TX_MSG).unwrap();
+        let expected = Sha256::digest(TX_MSG.as_bytes());
+        assert_eq!(HexBinary::from(expected.as_slice()).to_hex(), hash);
+    }
+
+    #[test]
+    fn same_hash_for_every_form() {
+        let hash = proposal_hash_of(TX_MSG).unwrap();
+
+        // Whitespace and field order don't matter
+        let reordered =
+            r#"{ "tx_bank": { "coins": [{"amount": "10", "denom": "usei"}], "to": "rcpt" } }"#;
+        assert_eq!(hash, proposal_hash_of(reordered).unwrap());
+
+        let create = format!(r#"{{"create_transaction":{{"tx_msg":{TX_MSG}}}}}"#);
+        assert_eq!(hash, proposal_hash_of(&create).unwrap());
+
+        let tx = Transaction::new(from_json(TX_MSG).unwrap(), 7);
+        let tx = String::from_utf8(to_json_vec(&tx).unwrap()).unwrap();
+        assert_eq!(hash, proposal_hash_of(&tx).unwrap());
+
+        let other = TX_MSG.replace("10", "11");
+        assert_ne!(hash, proposal_hash_of(&other).unwrap());
+    }
+
+    #[test]
+    fn rejects_other_messages() {
+        assert!(proposal_hash_of(r#"{"sign_transactions":{"tx_id":"1"}}"#).is_err());
+        assert!(proposal_hash_of("{}").is_err());
+    }
+}
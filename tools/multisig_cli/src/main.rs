@@ -0,0 +1,108 @@
+//! Offline tool composing multisig messages, hashing proposals and decoding the wallet's
+//! events. Messages are printed as JSON, ready to be passed to `seid tx wasm execute` or
+//! `seid q wasm contract-state smart`.
+
+use std::fs;
+use std::io::{self, Read};
+
+use anyhow::{Context, Result};
+use clap::{Parser, Subcommand};
+use cosmwasm_std::to_json_string;
+use multisig::msg::ExecuteMsg;
+use serde::Serialize;
+
+mod compose;
+mod decode;
+mod hash;
+
+#[derive(Parser)]
+#[command(name = "multisig-cli", version, about)]
+struct Cli {
+    /// Bech32 prefix of the chain's addresses
+    #[arg(long, global = true, default_value = "sei")]
+    prefix: String,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Prints a `create_transaction` message
+    Create {
+        /// Overrides the wallet's auto-execute setting for this transaction
+        #[arg(long)]
+        auto_execute: Option<bool>,
+
+        #[command(subcommand)]
+        tx: compose::TxArgs,
+    },
+    /// Prints a `sign_transactions` message
+    Sign {
+        #[arg(long)]
+        tx_id: u128,
+    },
+    /// Prints an `execute_transaction` message
+    Execute {
+        #[arg(long)]
+        tx_id: u128,
+    },
+    /// Prints a query message
+    Query {
+        #[command(subcommand)]
+        query: compose::QueryArgs,
+    },
+    /// Prints the proposal hash of a `TxMsg`, a `create_transaction` or `propose` message,
+    /// or a transaction as returned by the wallet's queries
+    Hash {
+        /// JSON file, `-` reads stdin
+        input: String,
+    },
+    /// Prints the wallet's events of a tx result, as returned by `seid q tx --output json`
+    Decode {
+        /// JSON file, `-` reads stdin
+        input: String,
+    },
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+
+    match cli.command {
+        Command::Create { auto_execute, tx } => print_msg(&ExecuteMsg::CreateTransaction {
+            tx_msg: tx.into_tx_msg(&cli.prefix)?,
+            auto_execute,
+        }),
+        Command::Sign { tx_id } => print_msg(&ExecuteMsg::SignTransactions { tx_id }),
+        Command::Execute { tx_id } => print_msg(&ExecuteMsg::ExecuteTransaction { tx_id }),
+        Command::Query { query } => print_msg(&query.into_query_msg(&cli.prefix)?),
+        Command::Hash { input } => {
+            println!("{}", hash::proposal_hash_of(&read_input(&input)?)?);
+            Ok(())
+        }
+        Command::Decode { input } => {
+            let tx_result = serde_json::from_str(&read_input(&input)?)
+                .context("the tx result isn't valid JSON")?;
+            for event in decode::wallet_events(&tx_result)? {
+                println!("{event}");
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Prints `msg` as the contract serializes it, `u128` ids are strings
+fn print_msg(msg: &impl Serialize) -> Result<()> {
+    let json: serde_json::Value = serde_json::from_str(&to_json_string(msg)?)?;
+    println!("{}", serde_json::to_string_pretty(&json)?);
+    Ok(())
+}
+
+fn read_input(input: &str) -> Result<String> {
+    if input == "-" {
+        let mut buf = String::new();
+        io::stdin().read_to_string(&mut buf)?;
+        return Ok(buf);
+    }
+    fs::read_to_string(input).with_context(|| format!("can't read {input}"))
+}